@@ -1,45 +1,123 @@
+use std::collections::VecDeque;
+use std::io::Error as IoError;
 use std::num::ParseIntError;
+use std::time::Instant;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_puzzle_part_time_two,
+    clap_arg_repeat, clap_arg_time, part_from_matches, print_answer, print_elapsed_time,
+    read_file_lines, read_input_contents, solve_repeatedly, Part, ReadFileContentsError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day01";
+pub const ABOUT: &str = "My solution for Day 1: Sonar Sweep";
+
+pub const EXAMPLE: &str = "199\r\n200\r\n208\r\n210\r\n200\r\n207\r\n240\r\n269\r\n260\r\n263";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 1: Sonar Sweep")
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day01-input"))
+        .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
         .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day01-input"),
+            Arg::with_name("window")
+                .short("w")
+                .long("window")
+                .value_name("SIZE")
+                .help("sets the size of the sliding window for part two")
+                .default_value("3"),
+        )
+        .arg(
+            Arg::with_name("stream")
+                .long("stream")
+                .help("reads the input file line by line instead of loading it completely"),
         )
-        .arg(clap_arg_puzzle_part_time_two())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day01Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    if matches.is_present("stream") && !matches.is_present("example") {
+        return handle_streaming(matches);
+    }
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day01Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => {
-            let increases_count =
-                count_depth_measurement_increases_three_sliding_window(&file_contents)?;
-            println!(
-                "Depth measurement increases (with sliding window of three) count is: {}",
-                increases_count
+    let solving_start = Instant::now();
+    match part_from_matches(matches) {
+        Part::Two => {
+            let window = matches.value_of("window").unwrap_or("3");
+            let window = window
+                .parse::<usize>()
+                .map_err(|error| Day01Error::ParseWindow(window.to_string(), error))?;
+            let increases_count = solve_repeatedly(matches, || {
+                count_sliding_window_increases(&file_contents, window)
+            })?;
+            print_answer(
+                matches,
+                1,
+                2,
+                increases_count,
+                format!(
+                    "Depth measurement increases (with sliding window of {}) count is: {}",
+                    window, increases_count
+                ),
             );
         }
-        _ => {
-            let increases_count = count_depth_measurement_increases(&file_contents)?;
-            println!("Depth measurement increases count is: {}", increases_count);
+        Part::One => {
+            let increases_count =
+                solve_repeatedly(matches, || count_depth_increases(&file_contents))?;
+            print_answer(
+                matches,
+                1,
+                1,
+                increases_count,
+                format!("Depth measurement increases count is: {}", increases_count),
+            );
         }
     }
+    print_elapsed_time(matches, solving_start);
+    Ok(())
+}
+
+fn handle_streaming(matches: &ArgMatches) -> Result<(), Day01Error> {
+    let input_file = matches.value_of("input_file");
+    let solving_start = Instant::now();
+    let (part, window) = match part_from_matches(matches) {
+        Part::Two => {
+            let window = matches.value_of("window").unwrap_or("3");
+            (
+                2,
+                window
+                    .parse::<usize>()
+                    .map_err(|error| Day01Error::ParseWindow(window.to_string(), error))?,
+            )
+        }
+        Part::One => (1, 1),
+    };
+    // the lines are consumed while counting, so every repetition streams the file again
+    let increases_count = solve_repeatedly(matches, || {
+        let lines = read_file_lines(input_file)
+            .map_err(|error| Day01Error::ReadFileContents(input_file.map(str::to_string), error))?;
+        Ok::<_, Day01Error>(count_sliding_window_increases_streaming(lines, window)?)
+    })?;
+    print_answer(
+        matches,
+        1,
+        part,
+        increases_count,
+        format!(
+            "Depth measurement increases (with sliding window of {}) count is: {}",
+            window, increases_count
+        ),
+    );
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -47,36 +125,82 @@ pub fn handle(matches: &ArgMatches) -> Result<(), Day01Error> {
 pub enum Day01Error {
     #[error("Could not read file contents of \"{0:?}\" ({1})")]
     ReadFileContents(Option<String>, #[source] ReadFileContentsError),
+    #[error("Could not parse window size \"{0}\" ({1})")]
+    ParseWindow(String, #[source] ParseIntError),
+    #[error(transparent)]
+    CountDepthIncreases(#[from] CountDepthIncreasesError),
     #[error(transparent)]
-    CountDepthMeasurementIncreases(#[from] CountDepthMeasurementIncreasesError),
+    CountSlidingWindowIncreases(#[from] CountSlidingWindowIncreasesError),
     #[error(transparent)]
-    CountDepthMeasurementIncreasesThreeSlidingWindow(
-        #[from] CountDepthMeasurementIncreasesThreeSlidingWindowError,
-    ),
+    CountSlidingWindowIncreasesStreaming(#[from] CountSlidingWindowIncreasesStreamingError),
 }
 
-pub fn count_depth_measurement_increases(
-    depth_measurements: &str,
-) -> Result<u128, CountDepthMeasurementIncreasesError> {
+pub fn count_depth_increases(depth_measurements: &str) -> Result<u128, CountDepthIncreasesError> {
     count_depth_measurement_increases_with_sliding_window(depth_measurements, 1).map_err(Into::into)
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
-pub enum CountDepthMeasurementIncreasesError {
+pub enum CountDepthIncreasesError {
     #[error(transparent)]
     StrToNumVec(#[from] StrToNumVecError),
 }
 
-pub fn count_depth_measurement_increases_three_sliding_window(
+pub fn count_sliding_window_increases(
     depth_measurement: &str,
-) -> Result<u128, CountDepthMeasurementIncreasesThreeSlidingWindowError> {
-    count_depth_measurement_increases_with_sliding_window(depth_measurement, 3).map_err(Into::into)
+    window: usize,
+) -> Result<u128, CountSlidingWindowIncreasesError> {
+    if window < 1 {
+        return Err(CountSlidingWindowIncreasesError::InvalidWindowSize(window));
+    }
+    count_depth_measurement_increases_with_sliding_window(depth_measurement, window)
+        .map_err(Into::into)
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
-pub enum CountDepthMeasurementIncreasesThreeSlidingWindowError {
+pub enum CountSlidingWindowIncreasesError {
     #[error(transparent)]
     StrToNumVec(#[from] StrToNumVecError),
+    #[error("Window size {0} is invalid, needs to be at least 1")]
+    InvalidWindowSize(usize),
+}
+
+pub fn count_sliding_window_increases_streaming<I: Iterator<Item = Result<String, IoError>>>(
+    lines: I,
+    window: usize,
+) -> Result<u128, CountSlidingWindowIncreasesStreamingError> {
+    if window < 1 {
+        return Err(CountSlidingWindowIncreasesStreamingError::InvalidWindowSize(window));
+    }
+    let mut increases_count = 0;
+    let mut current_window = VecDeque::with_capacity(window + 1);
+    for line in lines {
+        let line = line.map_err(CountSlidingWindowIncreasesStreamingError::ReadLine)?;
+        let line = line.trim_end_matches('\r');
+        if line.is_empty() {
+            continue;
+        }
+        current_window.push_back(line.parse::<u128>().map_err(|error| {
+            CountSlidingWindowIncreasesStreamingError::ParseInt(line.to_string(), error)
+        })?);
+        // both windows share all but the oldest and the newest value
+        if current_window.len() > window && current_window.back() > current_window.front() {
+            increases_count += 1;
+        }
+        if current_window.len() > window {
+            current_window.pop_front();
+        }
+    }
+    Ok(increases_count)
+}
+
+#[derive(Debug, Error)]
+pub enum CountSlidingWindowIncreasesStreamingError {
+    #[error("Could not read line ({0})")]
+    ReadLine(#[source] IoError),
+    #[error("Could not parse number \"{0}\" ({1})")]
+    ParseInt(String, #[source] ParseIntError),
+    #[error("Window size {0} is invalid, needs to be at least 1")]
+    InvalidWindowSize(usize),
 }
 
 fn count_depth_measurement_increases_with_sliding_window(
@@ -92,11 +216,11 @@ fn count_depth_measurement_increases_with_sliding_window(
 
 fn str_to_num_vec(content: &str) -> Result<Vec<u128>, StrToNumVecError> {
     content
-        .split(|c| c == '\r' || c == '\n')
+        .split(['\r', '\n'])
         .filter(|line| !line.is_empty())
         .map(|line| {
             line.parse::<u128>()
-                .map_err(|error| StrToNumVecError::Parse(line.to_string(), error))
+                .map_err(|error| StrToNumVecError::ParseInt(line.to_string(), error))
         })
         .collect::<Result<Vec<u128>, StrToNumVecError>>()
 }
@@ -104,7 +228,7 @@ fn str_to_num_vec(content: &str) -> Result<Vec<u128>, StrToNumVecError> {
 #[derive(Debug, Error, Eq, PartialEq)]
 pub enum StrToNumVecError {
     #[error("Could not parse number \"{0}\" ({1})")]
-    Parse(String, #[source] ParseIntError),
+    ParseInt(String, #[source] ParseIntError),
 }
 
 fn sliding_window<F: Fn(&[u128]) -> u128>(
@@ -149,10 +273,10 @@ mod tests {
     #[test]
     fn it_should_return_7() {
         // given
-        let input: &str = "199\r\n200\r\n208\r\n210\r\n200\r\n207\r\n240\r\n269\r\n260\r\n263";
+        let input: &str = EXAMPLE;
 
         // when
-        let result = count_depth_measurement_increases(&input);
+        let result = count_depth_increases(input);
 
         // then
         assert_eq!(result, Ok(7));
@@ -164,7 +288,7 @@ mod tests {
         let input: &str = "1\r\n2\r\n5\r\n8\r\n10\r\n33\r\n400";
 
         // when
-        let result = count_depth_measurement_increases(&input);
+        let result = count_depth_increases(input);
 
         // then
         assert_eq!(result, Ok(6));
@@ -176,7 +300,7 @@ mod tests {
         let input: &str = "100\r\n23\r\n18\r\n1";
 
         // when
-        let result = count_depth_measurement_increases(&input);
+        let result = count_depth_increases(input);
 
         // then
         assert_eq!(result, Ok(0));
@@ -185,12 +309,66 @@ mod tests {
     #[test]
     fn it_should_return_5() {
         // given
-        let input: &str = "199\r\n200\r\n208\r\n210\r\n200\r\n207\r\n240\r\n269\r\n260\r\n263";
+        let input: &str = EXAMPLE;
 
         // when
-        let result = count_depth_measurement_increases_three_sliding_window(&input);
+        let result = count_sliding_window_increases(input, 3);
 
         // then
         assert_eq!(result, Ok(5));
     }
+
+    #[test]
+    fn it_should_return_2() {
+        // given
+        let input: &str = "3\r\n1\r\n4\r\n1\r\n5";
+
+        // when
+        let result = count_sliding_window_increases(input, 2);
+
+        // then
+        assert_eq!(result, Ok(2));
+    }
+
+    #[test]
+    fn it_should_fail_with_window_0() {
+        // given
+        let input: &str = "3\r\n1\r\n4";
+
+        // when
+        let result = count_sliding_window_increases(input, 0);
+
+        // then
+        assert_eq!(
+            result,
+            Err(CountSlidingWindowIncreasesError::InvalidWindowSize(0))
+        );
+    }
+
+    #[test]
+    fn count_sliding_window_increases_streaming_should_match_string_based_count() {
+        // given
+        let input = (0..5000u128)
+            .map(|index| ((index * 7919) % 1000).to_string())
+            .collect::<Vec<String>>()
+            .join("\r\n");
+        let file_path =
+            std::env::temp_dir().join(format!("aoc2021-day01-streaming-{}", std::process::id()));
+        std::fs::write(&file_path, &input).unwrap();
+
+        for window in [1, 3] {
+            // when
+            let increases_count = count_sliding_window_increases_streaming(
+                read_file_lines(file_path.to_str()).unwrap(),
+                window,
+            );
+
+            // then
+            assert_eq!(
+                increases_count.ok(),
+                count_sliding_window_increases(&input, window).ok()
+            );
+        }
+        std::fs::remove_file(&file_path).unwrap();
+    }
 }
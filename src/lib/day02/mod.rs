@@ -1,25 +1,29 @@
+use std::num::ParseIntError;
+use std::str::FromStr;
+use std::time::Instant;
+
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_repeat, clap_arg_time,
+    part_from_matches, print_answer, print_elapsed_time, read_input_contents, solve_repeatedly,
+    Part, ReadFileContentsError,
+};
 
 pub mod part1;
 pub mod part2;
 
 pub const SUBCOMMAND_NAME: &str = "day02";
+pub const ABOUT: &str = "My solution for Day 2: Dive!";
+
+pub const EXAMPLE: &str = "forward 5\r\ndown 5\r\nforward 8\r\nup 3\r\ndown 8\r\nforward 2";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 2: Dive!")
-        .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day02-input"),
-        )
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day02-input"))
         .arg(
             Arg::with_name("puzzle_part")
                 .short("p")
@@ -29,24 +33,56 @@ pub fn subcommand() -> App<'static, 'static> {
                 .possible_values(&["one", "two", "1", "2"])
                 .default_value("two"),
         )
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day02Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day02Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => {
-            let mut submarine = part2::Submarine::default();
-            submarine.drive(&file_contents)?;
-            println!("Drove submarine to {:?}.", submarine.position);
+    let solving_start = Instant::now();
+    match part_from_matches(matches) {
+        Part::Two => {
+            let submarine = solve_repeatedly(matches, || {
+                let mut submarine = part2::Submarine::default();
+                submarine.drive(&file_contents).map(|_| submarine)
+            })?;
+            let ((horizontal, depth), aim) = (submarine.position(), submarine.aim());
+            print_answer(
+                matches,
+                2,
+                2,
+                horizontal * depth,
+                format!(
+                    "Drove submarine to horizontal position {} and depth {} with aim {} (multiplied {}).",
+                    horizontal,
+                    depth,
+                    aim,
+                    horizontal * depth
+                ),
+            );
         }
-        _ => {
-            let mut submarine = part1::Submarine::default();
-            submarine.drive(&file_contents)?;
-            println!("Drove submarine to {:?}.", submarine.position);
+        Part::One => {
+            let (horizontal, depth) =
+                solve_repeatedly(matches, || part1::final_position(&file_contents))?;
+            print_answer(
+                matches,
+                2,
+                1,
+                horizontal * depth,
+                format!(
+                    "Drove submarine to horizontal position {} and depth {} (multiplied {}).",
+                    horizontal,
+                    depth,
+                    horizontal * depth
+                ),
+            );
         }
     }
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -55,7 +91,97 @@ pub enum Day02Error {
     #[error("Could not read file contents of \"{0:?}\" ({1})")]
     ReadFileContents(Option<String>, #[source] ReadFileContentsError),
     #[error("Could not drive submarine ({0})")]
-    Part1SubmarineDrive(#[from] part1::SubmarineDriveError),
-    #[error("Could not drive submarine ({0})")]
-    Part2SubmarineDrive(#[from] part2::SubmarineDriveError),
+    SubmarineDrive(#[from] SubmarineDriveError),
+}
+
+pub fn parse_commands(course: &str) -> Result<Vec<Command>, SubmarineDriveError> {
+    course
+        .lines()
+        .enumerate()
+        .map(|(line_index, line)| (line_index + 1, line))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(line_nr, line)| {
+            Command::from_str(line).map_err(|error| match error {
+                CommandFromStrError::UnknownCommand(direction) => {
+                    SubmarineDriveError::UnknownCommand(direction, line.to_string(), line_nr)
+                }
+                CommandFromStrError::ParseNumber(error) => {
+                    SubmarineDriveError::LineParseNumber(line_nr, line.to_string(), error)
+                }
+                CommandFromStrError::WrongElementsCount(count) => {
+                    SubmarineDriveError::LineWrongElementsCount(line_nr, line.to_string(), count)
+                }
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum SubmarineDriveError {
+    #[error("Command \"{0}\" at line no. {2} \"{1}\" is unknown")]
+    UnknownCommand(String, String, usize),
+    #[error("Could not parse line no. {0} \"{1}\" ({2})")]
+    LineParseNumber(usize, String, ParseIntError),
+    #[error("Line no. {0} \"{1}\" has wrong ({2}) count of elements")]
+    LineWrongElementsCount(usize, String, usize),
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Command {
+    Forward(i64),
+    Down(i64),
+    Up(i64),
+}
+
+impl FromStr for Command {
+    type Err = CommandFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let elements: [&str; 2] =
+            s.split(' ')
+                .collect::<Vec<&str>>()
+                .try_into()
+                .map_err(|elements: Vec<&str>| {
+                    CommandFromStrError::WrongElementsCount(elements.len())
+                })?;
+        let units = elements[1]
+            .parse::<i64>()
+            .map_err(CommandFromStrError::ParseNumber)?;
+        match elements[0].to_lowercase().as_str() {
+            "forward" => Ok(Self::Forward(units)),
+            "down" => Ok(Self::Down(units)),
+            "up" => Ok(Self::Up(units)),
+            _ => Err(CommandFromStrError::UnknownCommand(elements[0].to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum CommandFromStrError {
+    #[error("Command \"{0}\" is unknown")]
+    UnknownCommand(String),
+    #[error("Could not parse number ({0})")]
+    ParseNumber(#[source] ParseIntError),
+    #[error("Wrong ({0}) count of elements")]
+    WrongElementsCount(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_commands_should_return_three_commands() {
+        // given
+        let course = "forward 5\ndown 3\nup 1";
+
+        // when
+        let commands = parse_commands(course);
+
+        // then
+        assert_eq!(
+            commands,
+            Ok(vec![Command::Forward(5), Command::Down(3), Command::Up(1)])
+        );
+    }
 }
@@ -1,6 +1,4 @@
-use std::num::ParseIntError;
-
-use thiserror::Error;
+use super::{parse_commands, Command, SubmarineDriveError};
 
 #[derive(Default)]
 pub struct Submarine {
@@ -9,65 +7,25 @@ pub struct Submarine {
 
 impl Submarine {
     pub fn drive(&mut self, course: &str) -> Result<(), SubmarineDriveError> {
-        course
-            .split(|c| c == '\r' || c == '\n')
-            .filter(|line| !line.is_empty())
-            .enumerate()
-            .map(|(line_nr, line)| {
-                let elements = line.split(' ').collect::<Vec<&str>>();
-                if elements.len() == 2 {
-                    match elements[1].parse::<u128>() {
-                        Ok(distance) => Ok((line_nr, line, elements[0], distance)),
-                        Err(error) => Err(SubmarineDriveError::LineParseNumber(
-                            line_nr,
-                            line.to_string(),
-                            error,
-                        )),
-                    }
-                } else {
-                    Err(SubmarineDriveError::LineWrongElementsCount(
-                        line_nr,
-                        line.to_string(),
-                        elements.len(),
-                    ))
-                }
-            })
-            .collect::<Result<Vec<(usize, &str, &str, u128)>, SubmarineDriveError>>()?
-            .into_iter()
-            .map(
-                |(line_nr, line, direction, distance)| match direction.to_lowercase().as_str() {
-                    "forward" => {
-                        self.position.forward(distance);
-                        Ok(())
-                    }
-                    "down" => {
-                        self.position.down(distance);
-                        Ok(())
-                    }
-                    "up" => {
-                        self.position.up(distance);
-                        Ok(())
-                    }
-                    _ => Err(SubmarineDriveError::UnknownCommand(
-                        direction.to_string(),
-                        line.to_string(),
-                        line_nr,
-                    )),
-                },
-            )
-            .collect::<Result<Vec<()>, SubmarineDriveError>>()
-            .map(|_| ())
+        for command in parse_commands(course)? {
+            match command {
+                Command::Forward(units) => self.position.forward(units),
+                Command::Down(units) => self.position.down(units),
+                Command::Up(units) => self.position.up(units),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn position(&self) -> (i64, i64) {
+        (self.position.horizontal as i64, self.position.depth as i64)
     }
 }
 
-#[derive(Debug, Error, Eq, PartialEq)]
-pub enum SubmarineDriveError {
-    #[error("Command \"{0}\" at line no. {2} \"{1}\" is unknown")]
-    UnknownCommand(String, String, usize),
-    #[error("Could not parse line no. {0} \"{1}\" ({2})")]
-    LineParseNumber(usize, String, ParseIntError),
-    #[error("Line no. {0} \"{1}\" has wrong ({2}) count of elements")]
-    LineWrongElementsCount(usize, String, usize),
+pub fn final_position(course: &str) -> Result<(i64, i64), SubmarineDriveError> {
+    let mut submarine = Submarine::default();
+    submarine.drive(course)?;
+    Ok(submarine.position())
 }
 
 #[derive(Debug, Default, Eq, PartialEq)]
@@ -83,19 +41,25 @@ impl Position {
         Self { horizontal, depth }
     }
 
-    fn forward(&mut self, distance: u128) {
+    fn forward(&mut self, distance: i64) {
         self.horizontal = self
             .horizontal
-            .checked_add(distance)
+            .checked_add_signed(distance as i128)
             .unwrap_or(self.horizontal);
     }
 
-    fn down(&mut self, distance: u128) {
-        self.depth = self.depth.checked_add(distance).unwrap_or(self.depth);
+    fn down(&mut self, distance: i64) {
+        self.depth = self
+            .depth
+            .checked_add_signed(distance as i128)
+            .unwrap_or(self.depth);
     }
 
-    fn up(&mut self, distance: u128) {
-        self.depth = self.depth.checked_sub(distance).unwrap_or(self.depth);
+    fn up(&mut self, distance: i64) {
+        self.depth = self
+            .depth
+            .checked_add_signed(-(distance as i128))
+            .unwrap_or(self.depth);
     }
 }
 
@@ -156,15 +120,14 @@ mod tests {
     fn test_submarine_drive_random() {
         // given
         let random_course = (1..(rand::random::<f64>() * 16f64) as u8)
-            .into_iter()
             .map(|_| {
-                let direction: (&str, fn(&mut Position, u128)) =
+                let direction: (&str, fn(&mut Position, i64)) =
                     match (rand::random::<f64>() * 3f64) as u8 {
                         0 => ("forward", Position::forward),
                         1 => ("down", Position::down),
                         _ => ("up", Position::up),
                     };
-                let distance = rand::random::<u8>() as u128;
+                let distance = rand::random::<u8>() as i64;
                 (direction.0, distance, direction.1)
             })
             .fold(
@@ -286,4 +249,56 @@ mod tests {
         assert_eq!(position.horizontal, 1);
         assert_eq!(position.depth, 4);
     }
+
+    #[test]
+    fn test_final_position_example() {
+        // given
+        let course = "forward 5\r\ndown 5\r\nforward 8\r\nup 3\r\ndown 8\r\nforward 2";
+
+        // when
+        let final_position = final_position(course);
+
+        // then
+        assert_eq!(final_position, Ok((15, 10)));
+    }
+
+    #[test]
+    fn test_submarine_drive_should_report_line_of_unknown_command() {
+        // given
+        let course = "forward 5\r\ndown 5\r\nbackward 8\r\nup 3";
+        let mut submarine = Submarine::default();
+
+        // when
+        let drive = submarine.drive(course);
+
+        // then
+        assert_eq!(
+            drive,
+            Err(SubmarineDriveError::UnknownCommand(
+                "backward".to_string(),
+                "backward 8".to_string(),
+                3
+            ))
+        );
+    }
+
+    #[test]
+    fn test_submarine_drive_should_report_line_of_wrong_elements_count() {
+        // given
+        let course = "forward 5\r\n\r\ndown 5 3\r\nup 3";
+        let mut submarine = Submarine::default();
+
+        // when
+        let drive = submarine.drive(course);
+
+        // then
+        assert_eq!(
+            drive,
+            Err(SubmarineDriveError::LineWrongElementsCount(
+                3,
+                "down 5 3".to_string(),
+                3
+            ))
+        );
+    }
 }
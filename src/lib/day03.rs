@@ -1,41 +1,80 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::time::Instant;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_puzzle_part_time_two,
+    clap_arg_repeat, clap_arg_time, part_from_matches, print_answer, print_elapsed_time,
+    read_input_contents, solve_repeatedly, Part, ReadFileContentsError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day03";
+pub const ABOUT: &str = "My solution for Day 3: Binary Diagnostic";
+
+pub const EXAMPLE: &str = "00100\r\n11110\r\n10110\r\n10111\r\n10101\r\n01111\r\n00111\r\n11100\r\n10000\r\n11001\r\n00010\r\n01010";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 3: Binary Diagnostic")
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day03-input"))
+        .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
         .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day03-input"),
+            Arg::with_name("tie_break")
+                .long("tie-break")
+                .value_name("TIE_BREAK")
+                .help(
+                    "selects the gamma rate bit of power consumption columns with equal 0s and 1s",
+                )
+                .possible_values(&["prefer-one", "prefer-zero", "error"])
+                .default_value("prefer-one"),
         )
-        .arg(clap_arg_puzzle_part_time_two())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day03Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day03Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => {
-            let life_support_rating = extract_life_support_rating(&file_contents)?;
-            println!("Extracted {:?}.", life_support_rating);
+    let solving_start = Instant::now();
+    match part_from_matches(matches) {
+        Part::Two => {
+            let life_support_rating =
+                solve_repeatedly(matches, || extract_life_support_rating(&file_contents))?;
+            print_answer(
+                matches,
+                3,
+                2,
+                life_support_rating.life_support_rating(),
+                format!("Extracted {}.", life_support_rating),
+            );
         }
-        _ => {
-            let power_consumption = extract_power_consumption(&file_contents)?;
-            println!("Extracted {:?}.", power_consumption);
+        Part::One => {
+            let tie_break = match matches.value_of("tie_break").unwrap_or("prefer-one") {
+                "prefer-zero" => TieBreak::PreferZero,
+                "error" => TieBreak::Error,
+                _ => TieBreak::PreferOne,
+            };
+            let power_consumption = solve_repeatedly(matches, || {
+                extract_power_consumption(&file_contents, tie_break)
+            })?;
+            print_answer(
+                matches,
+                3,
+                1,
+                power_consumption.power_consumption(),
+                format!("Extracted {}.", power_consumption),
+            );
         }
     }
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -51,9 +90,17 @@ pub enum Day03Error {
 
 pub fn extract_power_consumption(
     diagnostic_report: &str,
+    tie_break: TieBreak,
 ) -> Result<PowerConsumption, ExtractPowerConsumptionError> {
+    if let Some((line, expected, got)) = find_inconsistent_line_width(diagnostic_report) {
+        return Err(ExtractPowerConsumptionError::InconsistentLineWidth {
+            line,
+            expected,
+            got,
+        });
+    }
     let buckets: HashMap<usize, (u128, u128)> = diagnostic_report
-        .split(|c| c == '\r' || c == '\n')
+        .split(['\r', '\n'])
         .filter(|line| !line.is_empty())
         .fold(HashMap::new(), |mut buckets, next| {
             next.chars().enumerate().for_each(|(index, c)| {
@@ -79,12 +126,20 @@ pub fn extract_power_consumption(
         let max_index = *buckets.keys().max().unwrap();
         for index in 0..=max_index {
             let counts = buckets.get(&index).unwrap_or(&(0, 0));
-            if counts.0 == counts.1 {
-                eprintln!("Warning: index {} has equal 0s and 1s", index);
-            }
+            let gamma_bit_is_one = match counts.0.cmp(&counts.1) {
+                Ordering::Less => true,
+                Ordering::Greater => false,
+                Ordering::Equal => match tie_break {
+                    TieBreak::PreferOne => true,
+                    TieBreak::PreferZero => false,
+                    TieBreak::Error => {
+                        return Err(ExtractPowerConsumptionError::AmbiguousColumn(index))
+                    }
+                },
+            };
             gamma_rate <<= 1;
             epsilon_rate <<= 1;
-            if counts.0 <= counts.1 {
+            if gamma_bit_is_one {
                 // bit for gamma is 1 and for epsilon is 0
                 gamma_rate |= 1;
             } else {
@@ -100,6 +155,22 @@ pub fn extract_power_consumption(
 pub enum ExtractPowerConsumptionError {
     #[error("Every line is empty")]
     EveryLineEmpty,
+    #[error("Column {0} has equal 0s and 1s")]
+    AmbiguousColumn(usize),
+    #[error("Line no. {line} has a width of {got}, but {expected} was expected")]
+    InconsistentLineWidth {
+        line: usize,
+        expected: usize,
+        got: usize,
+    },
+}
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum TieBreak {
+    #[default]
+    PreferOne,
+    PreferZero,
+    Error,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -115,6 +186,22 @@ impl PowerConsumption {
             epsilon_rate,
         }
     }
+
+    pub fn power_consumption(&self) -> u32 {
+        self.gamma_rate as u32 * self.epsilon_rate as u32
+    }
+}
+
+impl Display for PowerConsumption {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "power consumption {} (gamma rate {} times epsilon rate {})",
+            self.power_consumption(),
+            self.gamma_rate,
+            self.epsilon_rate
+        )
+    }
 }
 
 pub fn extract_life_support_rating(
@@ -158,8 +245,15 @@ fn extract_rating<F: Fn(usize, (u16, u16)) -> G, G: Fn(&&str) -> bool>(
     diagnostic_report: &str,
     filter: F,
 ) -> Result<u16, ExtractRatingError> {
+    if let Some((line, expected, got)) = find_inconsistent_line_width(diagnostic_report) {
+        return Err(ExtractRatingError::InconsistentLineWidth {
+            line,
+            expected,
+            got,
+        });
+    }
     let mut lines = diagnostic_report
-        .split(|c| c == '\r' || c == '\n')
+        .split(['\r', '\n'])
         .filter(|line| !line.is_empty())
         .collect::<Vec<&str>>();
     let mut current_bit_index = 0;
@@ -218,6 +312,26 @@ pub enum ExtractRatingError {
     CharNotZeroOrOne(char),
     #[error("Last iteration over bit index {0} left zero elements")]
     IterationLeftZeroElements(usize),
+    #[error("Line no. {line} has a width of {got}, but {expected} was expected")]
+    InconsistentLineWidth {
+        line: usize,
+        expected: usize,
+        got: usize,
+    },
+}
+
+// returns the (1-based) line number, expected and actual width of the first line
+// whose width differs from the first non-empty line
+fn find_inconsistent_line_width(diagnostic_report: &str) -> Option<(usize, usize, usize)> {
+    let mut non_empty_lines = diagnostic_report
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty());
+    let (_, first_line) = non_empty_lines.next()?;
+    let expected = first_line.chars().count();
+    non_empty_lines
+        .map(|(line_index, line)| (line_index + 1, expected, line.chars().count()))
+        .find(|(_, expected, got)| expected != got)
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -233,6 +347,22 @@ impl LifeSupportRating {
             co2_scrubber_rating,
         }
     }
+
+    pub fn life_support_rating(&self) -> u32 {
+        self.oxygen_generator_rating as u32 * self.co2_scrubber_rating as u32
+    }
+}
+
+impl Display for LifeSupportRating {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "life support rating {} (oxygen generator rating {} times CO2 scrubber rating {})",
+            self.life_support_rating(),
+            self.oxygen_generator_rating,
+            self.co2_scrubber_rating
+        )
+    }
 }
 
 #[cfg(test)]
@@ -255,7 +385,7 @@ mod tests {
         let input = "000\r\n000\r\n000";
 
         // when
-        let power_consumption = extract_power_consumption(input);
+        let power_consumption = extract_power_consumption(input, TieBreak::default());
 
         // then
         assert_eq!(power_consumption, Ok(PowerConsumption::of(0, 7)));
@@ -264,15 +394,73 @@ mod tests {
     #[test]
     fn extract_power_consumption_should_return_22_9() {
         // given
-        let input = "00100\r\n11110\r\n10110\r\n10111\r\n10101\r\n01111\r\n00111\r\n11100\r\n10000\r\n11001\r\n00010\r\n01010";
+        let input = EXAMPLE;
 
         // when
-        let power_consumption = extract_power_consumption(input);
+        let power_consumption = extract_power_consumption(input, TieBreak::default());
 
         // then
         assert_eq!(power_consumption, Ok(PowerConsumption::of(22, 9)));
     }
 
+    #[test]
+    fn extract_power_consumption_with_tie_and_prefer_one_should_return_1_2() {
+        // given
+        let input = "01\r\n00";
+
+        // when
+        let power_consumption = extract_power_consumption(input, TieBreak::PreferOne);
+
+        // then
+        assert_eq!(power_consumption, Ok(PowerConsumption::of(1, 2)));
+    }
+
+    #[test]
+    fn extract_power_consumption_with_tie_and_prefer_zero_should_return_0_3() {
+        // given
+        let input = "01\r\n00";
+
+        // when
+        let power_consumption = extract_power_consumption(input, TieBreak::PreferZero);
+
+        // then
+        assert_eq!(power_consumption, Ok(PowerConsumption::of(0, 3)));
+    }
+
+    #[test]
+    fn extract_power_consumption_with_tie_and_error_should_fail() {
+        // given
+        let input = "01\r\n00";
+
+        // when
+        let power_consumption = extract_power_consumption(input, TieBreak::Error);
+
+        // then
+        assert_eq!(
+            power_consumption,
+            Err(ExtractPowerConsumptionError::AmbiguousColumn(1))
+        );
+    }
+
+    #[test]
+    fn extract_power_consumption_with_inconsistent_line_width_should_fail() {
+        // given
+        let input = "00100\r\n11110\r\n1011\r\n10111";
+
+        // when
+        let power_consumption = extract_power_consumption(input, TieBreak::default());
+
+        // then
+        assert_eq!(
+            power_consumption,
+            Err(ExtractPowerConsumptionError::InconsistentLineWidth {
+                line: 3,
+                expected: 5,
+                got: 4
+            })
+        );
+    }
+
     #[test]
     fn life_support_rating_of() {
         // given
@@ -297,7 +485,7 @@ mod tests {
         let input = "000\r\n010\r\n101";
 
         // when
-        let life_support_rating = extract_life_support_rating(&input);
+        let life_support_rating = extract_life_support_rating(input);
 
         // then
         assert_eq!(life_support_rating, Ok(LifeSupportRating::of(2, 5)));
@@ -306,12 +494,65 @@ mod tests {
     #[test]
     fn extract_life_support_rating_should_return_23_10() {
         // given
-        let input = "00100\r\n11110\r\n10110\r\n10111\r\n10101\r\n01111\r\n00111\r\n11100\r\n10000\r\n11001\r\n00010\r\n01010";
+        let input = EXAMPLE;
 
         // when
-        let life_support_rating = extract_life_support_rating(&input);
+        let life_support_rating = extract_life_support_rating(input);
 
         // then
         assert_eq!(life_support_rating, Ok(LifeSupportRating::of(23, 10)));
     }
+
+    #[test]
+    fn extract_life_support_rating_with_inconsistent_line_width_should_fail() {
+        // given
+        let input = "000\r\n0101\r\n101";
+
+        // when
+        let life_support_rating = extract_life_support_rating(input);
+
+        // then
+        assert_eq!(
+            life_support_rating,
+            Err(ExtractLifeSupportRatingError::ExtractOxygenGeneratorRating(
+                ExtractRatingError::InconsistentLineWidth {
+                    line: 2,
+                    expected: 3,
+                    got: 4
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn power_consumption_of_example_should_be_198() {
+        // given
+        let power_consumption = PowerConsumption::of(22, 9);
+
+        // when
+        let product = power_consumption.power_consumption();
+
+        // then
+        assert_eq!(product, 198);
+        assert_eq!(
+            power_consumption.to_string(),
+            "power consumption 198 (gamma rate 22 times epsilon rate 9)"
+        );
+    }
+
+    #[test]
+    fn life_support_rating_of_example_should_be_230() {
+        // given
+        let life_support_rating = LifeSupportRating::of(23, 10);
+
+        // when
+        let product = life_support_rating.life_support_rating();
+
+        // then
+        assert_eq!(product, 230);
+        assert_eq!(
+            life_support_rating.to_string(),
+            "life support rating 230 (oxygen generator rating 23 times CO2 scrubber rating 10)"
+        );
+    }
 }
@@ -1,38 +1,103 @@
+use std::cmp::Ordering;
 use std::num::ParseIntError;
 use std::str::FromStr;
+use std::time::Instant;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_puzzle_part_time_two,
+    clap_arg_repeat, clap_arg_time, part_from_matches, print_answer, print_elapsed_time,
+    read_input_contents, solve_repeatedly, InputShapeError, Part, ReadFileContentsError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day04";
+pub const ABOUT: &str = "My solution for Day 4: Giant Squid";
+
+pub const EXAMPLE: &str = "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1\
+    \r\n\r\n22 13 17 11  0\r\n 8  2 23  4 24\r\n21  9 14 16  7\
+    \r\n 6 10  3 18  5\r\n 1 12 20 15 19\r\n\r\n 3 15  0  2 22\
+    \r\n 9 18 13 17  5\r\n19  8  7 25 23\r\n20 11 10 24  4\r\n\
+    14 21 16 12  6\r\n\r\n14 21 17 24  4\r\n10 16 15  9 19\r\n\
+    18  8 23 26 20\r\n22 11 13  6  5\r\n 2  0 12  3  7";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 4: Giant Squid")
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day04-input"))
+        .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
         .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day04-input"),
+            Arg::with_name("finish_order")
+                .long("finish-order")
+                .help("prints the scores of every board in the order they win to stderr"),
+        )
+        .arg(
+            Arg::with_name("board")
+                .long("board")
+                .value_name("BOARD")
+                .help("overrides the board of the part (winning, first, losing, loosing or last)"),
+        )
+        .arg(
+            Arg::with_name("draw_limit")
+                .long("draw-limit")
+                .value_name("COUNT")
+                .help("stops drawing numbers after the given count (defaults to unlimited)"),
         )
-        .arg(clap_arg_puzzle_part_time_two())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day04Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day04Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    let board_selection = match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => BoardSelection::Loosing,
-        _ => BoardSelection::Winning,
+    let solving_start = Instant::now();
+    let (part, board_selection) = match part_from_matches(matches) {
+        Part::Two => (2, BoardSelection::Loosing),
+        Part::One => (1, BoardSelection::Winning),
     };
-    let scores = calculate_winning_bingo_board_scores(&file_contents, board_selection)?;
-    println!("The {} bingo board has {:?}.", board_selection, scores);
+    let board_selection = match matches.value_of("board") {
+        Some(board) => BoardSelection::from_str(board)?,
+        None => board_selection,
+    };
+    let draw_limit = matches
+        .value_of("draw_limit")
+        .map(|value| {
+            value
+                .parse::<usize>()
+                .map_err(|error| Day04Error::ParseDrawLimit(value.to_string(), error))
+        })
+        .transpose()?;
+    let (scores, finish_order) = solve_repeatedly(matches, || {
+        calculate_board_scores_and_finish_order(&file_contents, board_selection, draw_limit)
+    })?;
+    if matches.is_present("finish_order") {
+        for (place, scores) in finish_order.iter().enumerate() {
+            eprintln!(
+                "{}. board won with unmarked sum {} on number {} (final score {})",
+                place + 1,
+                scores.sum_all_unmarked_numbers(),
+                scores.lastly_called_number(),
+                scores.final_score()
+            );
+        }
+    }
+    print_answer(
+        matches,
+        4,
+        part,
+        scores.final_score(),
+        format!(
+            "The {} bingo board has a final score of {}.",
+            board_selection,
+            scores.final_score()
+        ),
+    );
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -42,100 +107,164 @@ pub enum Day04Error {
     ReadFileContents(Option<String>, #[source] ReadFileContentsError),
     #[error("Could not calculate winning bingo board scores ({0})")]
     CalculateWinningBingoBoardScores(#[from] CalculateWinningBingoBoardScoresError),
+    #[error("Could not parse board selection ({0})")]
+    BoardSelectionFromStr(#[from] BoardSelectionFromStrError),
+    #[error("Could not parse draw limit \"{0}\" ({1})")]
+    ParseDrawLimit(String, #[source] ParseIntError),
 }
 
 pub fn calculate_winning_bingo_board_scores(
     bingo_play_data: &str,
     board_selection: BoardSelection,
+    draw_limit: Option<usize>,
 ) -> Result<Scores, CalculateWinningBingoBoardScoresError> {
-    let (drawn_number_strings, bingo_board_strings): (Option<&str>, Vec<Vec<&str>>) =
+    calculate_board_scores_and_finish_order(bingo_play_data, board_selection, draw_limit)
+        .map(|(scores, _)| scores)
+}
+
+pub fn calculate_board_scores_and_finish_order(
+    bingo_play_data: &str,
+    board_selection: BoardSelection,
+    draw_limit: Option<usize>,
+) -> Result<(Scores, Vec<Scores>), CalculateWinningBingoBoardScoresError> {
+    let (finish_order, count_of_boards) =
+        calculate_board_finish_order(bingo_play_data, draw_limit)?;
+    if let Some(draw_limit) = draw_limit.filter(|_| finish_order.is_empty()) {
+        return Err(CalculateWinningBingoBoardScoresError::NoBoardWonWithinLimit(draw_limit));
+    }
+    let scores = match board_selection {
+        BoardSelection::Winning => finish_order.first(),
+        BoardSelection::Loosing if finish_order.len() == count_of_boards => finish_order.last(),
+        _ => None,
+    }
+    .copied()
+    .ok_or(CalculateWinningBingoBoardScoresError::NoBoardWon)?;
+    Ok((scores, finish_order))
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum CalculateWinningBingoBoardScoresError {
+    #[error(transparent)]
+    BoardFinishOrder(#[from] BoardFinishOrderError),
+    #[error("No bingo board won")]
+    NoBoardWon,
+    #[error("No bingo board won within the first {0} drawn numbers")]
+    NoBoardWonWithinLimit(usize),
+}
+
+pub fn board_finish_order(bingo_play_data: &str) -> Result<Vec<Scores>, BoardFinishOrderError> {
+    Ok(calculate_board_finish_order(bingo_play_data, None)?.0)
+}
+
+fn calculate_board_finish_order(
+    bingo_play_data: &str,
+    draw_limit: Option<usize>,
+) -> Result<(Vec<Scores>, usize), BoardFinishOrderError> {
+    // blocks are separated by blank lines, the first one holds the drawn numbers
+    let mut blocks: Vec<Vec<&str>> =
         bingo_play_data
             .lines()
-            .filter(|line| !line.is_empty())
-            .fold((None, Vec::new()), |(mut a, mut b), next| {
-                if a.is_none() {
-                    a = Some(next);
-                } else {
-                    if b.is_empty() || b[b.len() - 1].len() >= 5 {
-                        b.push(Vec::new());
+            .fold(vec![Vec::new()], |mut blocks, next| {
+                if next.trim().is_empty() {
+                    if !blocks[blocks.len() - 1].is_empty() {
+                        blocks.push(Vec::new());
                     }
-                    let current_index = b.len() - 1;
-                    b[current_index].push(next);
+                } else {
+                    let current_index = blocks.len() - 1;
+                    blocks[current_index].push(next);
                 }
-                (a, b)
+                blocks
             });
-    let drawn_numbers = drawn_number_strings
-        .ok_or(CalculateWinningBingoBoardScoresError::MissingDrawnNumbers)?
+    blocks.retain(|block| !block.is_empty());
+    if blocks.is_empty() {
+        return Err(InputShapeError::Empty.into());
+    }
+    let bingo_board_strings = blocks.split_off(1);
+    // a wrapped line of drawn numbers continues after the line break
+    let drawn_numbers = blocks[0]
+        .iter()
+        .map(|line| line.trim().trim_end_matches(','))
+        .collect::<Vec<&str>>()
+        .join(",")
         .split(',')
         .map(|value| {
-            value.parse::<u8>().map_err(|error| {
-                CalculateWinningBingoBoardScoresError::ParseDrawnNumbers(value.to_string(), error)
-            })
+            value
+                .trim()
+                .parse::<u8>()
+                .map_err(|error| BoardFinishOrderError::ParseDrawnNumbers(value.to_string(), error))
         })
-        .collect::<Result<Vec<u8>, CalculateWinningBingoBoardScoresError>>()?;
-    let mut bingo_boards = bingo_board_strings
+        .collect::<Result<Vec<u8>, BoardFinishOrderError>>()?;
+    let bingo_boards = bingo_board_strings
         .into_iter()
         .map(|bingo_board| bingo_board.join("\r\n"))
         .map(|bingo_board| {
-            BingoBoard::from_str(&bingo_board).map_err(|error| {
-                CalculateWinningBingoBoardScoresError::BingoBoardFromStr(bingo_board, error)
-            })
+            BingoBoard::from_str(&bingo_board)
+                .map_err(|error| BoardFinishOrderError::BingoBoardFromStr(bingo_board, error))
         })
-        .collect::<Result<Vec<BingoBoard>, CalculateWinningBingoBoardScoresError>>()?;
-
-    let mut optional_winning_board = None;
-    let mut optional_last_drawn_number = None;
-    for drawn_number in drawn_numbers {
-        optional_last_drawn_number = Some(drawn_number);
-        for bingo_board in &mut bingo_boards {
-            bingo_board.mark(drawn_number);
-            if board_selection == BoardSelection::Winning && bingo_board.contains_bingo() {
-                optional_winning_board = Some(bingo_board.clone());
-                break;
-            }
+        .collect::<Result<Vec<BingoBoard>, BoardFinishOrderError>>()?;
+    if let Some(board_size) = bingo_boards.first().map(BingoBoard::size) {
+        if let Some((index, bingo_board)) = bingo_boards
+            .iter()
+            .enumerate()
+            .find(|(_, bingo_board)| bingo_board.size() != board_size)
+        {
+            return Err(BoardFinishOrderError::BoardSizeMismatch(
+                index,
+                board_size,
+                bingo_board.size(),
+            ));
         }
-        if board_selection == BoardSelection::Loosing {
-            if bingo_boards.len() == 1 && bingo_boards[0].contains_bingo() {
-                optional_winning_board = Some(bingo_boards[0].clone());
-            } else {
-                bingo_boards.retain(|bingo_board| !bingo_board.contains_bingo());
+    }
+
+    if drawn_numbers.is_empty() {
+        return Err(BoardFinishOrderError::NoNumberHasBeenDrawn);
+    }
+
+    let mut bingo_boards = bingo_boards
+        .iter()
+        .map(BitmaskBingoBoard::from)
+        .collect::<Vec<BitmaskBingoBoard>>();
+    let mut finish_order = Vec::new();
+    let mut finished = vec![false; bingo_boards.len()];
+    for drawn_number in drawn_numbers
+        .into_iter()
+        .take(draw_limit.unwrap_or(usize::MAX))
+    {
+        for (index, bingo_board) in bingo_boards.iter_mut().enumerate() {
+            if finished[index] {
+                continue;
+            }
+            if bingo_board.mark_returning_win(drawn_number) {
+                finished[index] = true;
+                finish_order.push(Scores::of(
+                    bingo_board
+                        .get_unmarked_cell_values()
+                        .iter()
+                        .map(|v| (*v) as u16)
+                        .sum(),
+                    drawn_number,
+                ));
             }
         }
-        if optional_winning_board.is_some() {
-            break;
-        }
-    }
-    match optional_last_drawn_number {
-        None => Err(CalculateWinningBingoBoardScoresError::NoNumberHasBeenDrawn),
-        Some(last_drawn_number) => match optional_winning_board {
-            None => Err(CalculateWinningBingoBoardScoresError::NoBoardWon),
-            Some(winning_board) => Ok(Scores::of(
-                winning_board
-                    .get_unmarked_cell_values()
-                    .iter()
-                    .map(|v| (*v) as u16)
-                    .sum(),
-                last_drawn_number,
-            )),
-        },
     }
+    Ok((finish_order, bingo_boards.len()))
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
-pub enum CalculateWinningBingoBoardScoresError {
-    #[error("Missing line with drawn numbers")]
-    MissingDrawnNumbers,
+pub enum BoardFinishOrderError {
+    #[error("Input has an unexpected shape ({0})")]
+    InputShape(#[from] InputShapeError),
     #[error("Could not parse drawn number \"{0}\" ({1})")]
     ParseDrawnNumbers(String, #[source] ParseIntError),
     #[error("Could not parse bingo board \"{0}\" ({1})")]
     BingoBoardFromStr(String, #[source] BingoBoardFromStrError),
     #[error("No number has been drawn")]
     NoNumberHasBeenDrawn,
-    #[error("No bingo board won")]
-    NoBoardWon,
+    #[error("Bingo board no. {0} should have size {1}, but has size {2}")]
+    BoardSizeMismatch(usize, usize, usize),
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct Scores {
     sum_all_unmarked_numbers: u16,
     lastly_called_number: u8,
@@ -148,46 +277,106 @@ impl Scores {
             lastly_called_number,
         }
     }
+
+    pub fn sum_all_unmarked_numbers(&self) -> u16 {
+        self.sum_all_unmarked_numbers
+    }
+
+    pub fn lastly_called_number(&self) -> u8 {
+        self.lastly_called_number
+    }
+
+    pub fn final_score(&self) -> u32 {
+        self.sum_all_unmarked_numbers as u32 * self.lastly_called_number as u32
+    }
+}
+
+impl Ord for Scores {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // tie breaking on the fields keeps the ordering consistent with the structural Eq
+        self.final_score()
+            .cmp(&other.final_score())
+            .then(
+                self.sum_all_unmarked_numbers
+                    .cmp(&other.sum_all_unmarked_numbers),
+            )
+            .then(self.lastly_called_number.cmp(&other.lastly_called_number))
+    }
+}
+
+impl PartialOrd for Scores {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 struct BingoBoard {
-    cells: [[u8; 5]; 5],
-    marked: [[bool; 5]; 5],
+    cells: Vec<Vec<u8>>,
+    marked: Vec<Vec<bool>>,
 }
 
 impl BingoBoard {
-    fn mark(&mut self, number: u8) {
-        for y in 0..5 {
-            for x in 0..5 {
-                if self.cells[y][x] == number {
-                    self.marked[y][x] = true;
-                }
-            }
-        }
+    fn size(&self) -> usize {
+        self.cells.len()
     }
+}
 
-    fn contains_bingo(&self) -> bool {
-        for column in 0..5 {
-            if self.marked.iter().all(|line| line[column]) {
-                return true;
+// the marks are tracked as bits, so that a bingo is a row or column mask being fully set
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct BitmaskBingoBoard {
+    cells: Vec<u8>,
+    size: usize,
+    marked: u128,
+    row_mask: u128,
+    column_mask: u128,
+}
+
+impl BitmaskBingoBoard {
+    fn mark_returning_win(&mut self, number: u8) -> bool {
+        let mut won = false;
+        for index in 0..self.cells.len() {
+            if self.cells[index] == number {
+                self.marked |= 1 << index;
+                let row_mask = self.row_mask << (index - index % self.size);
+                let column_mask = self.column_mask << (index % self.size);
+                won |=
+                    self.marked & row_mask == row_mask || self.marked & column_mask == column_mask;
             }
         }
-        self.marked
-            .iter()
-            .any(|line| line.iter().all(|value| *value))
+        won
     }
 
     fn get_unmarked_cell_values(&self) -> Vec<u8> {
-        let mut output = Vec::new();
-        for y in 0..5 {
-            for x in 0..5 {
-                if !self.marked[y][x] {
-                    output.push(self.cells[y][x]);
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| self.marked & (1 << index) == 0)
+            .map(|(_, cell)| *cell)
+            .collect()
+    }
+}
+
+impl From<&BingoBoard> for BitmaskBingoBoard {
+    fn from(bingo_board: &BingoBoard) -> Self {
+        let size = bingo_board.size();
+        let row_mask = (1u128 << size) - 1;
+        let column_mask = (0..size).fold(0u128, |mask, y| mask | 1 << (y * size));
+        let mut marked = 0;
+        for (y, line) in bingo_board.marked.iter().enumerate() {
+            for (x, value) in line.iter().enumerate() {
+                if *value {
+                    marked |= 1 << (y * size + x);
                 }
             }
         }
-        output
+        Self {
+            cells: bingo_board.cells.concat(),
+            size,
+            marked,
+            row_mask,
+            column_mask,
+        }
     }
 }
 
@@ -195,34 +384,42 @@ impl FromStr for BingoBoard {
     type Err = BingoBoardFromStrError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self {
-            cells: s
-                .lines()
-                .filter(|line| !line.is_empty())
-                .map(|line| {
-                    line.split(' ')
-                        .filter(|value| !value.is_empty())
-                        .map(|value| {
-                            value.parse::<u8>().map_err(|error| {
-                                BingoBoardFromStrError::Parse(value.to_string(), error)
-                            })
+        let cells = s
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                line.split(' ')
+                    .filter(|value| !value.is_empty())
+                    .map(|value| {
+                        value.parse::<u8>().map_err(|error| {
+                            BingoBoardFromStrError::Parse(value.to_string(), error)
                         })
-                        .collect::<Result<Vec<u8>, BingoBoardFromStrError>>()
-                })
-                .collect::<Result<Vec<Vec<u8>>, BingoBoardFromStrError>>()?
-                .into_iter()
-                .enumerate()
-                .map(|(line_no, line)| {
-                    line.try_into().map_err(|line| {
-                        BingoBoardFromStrError::LineCountOfElementsNotFive(line_no, line)
                     })
-                })
-                .collect::<Result<Vec<[u8; 5]>, BingoBoardFromStrError>>()?
-                .try_into()
-                .map_err(|lines: Vec<[u8; 5]>| {
-                    BingoBoardFromStrError::LineCountNotFive(lines.len(), lines)
-                })?,
-            marked: [[false; 5]; 5],
+                    .collect::<Result<Vec<u8>, BingoBoardFromStrError>>()
+            })
+            .collect::<Result<Vec<Vec<u8>>, BingoBoardFromStrError>>()?;
+        // the board is square, so its size is given by the count of lines
+        let board_size = cells.len();
+        if board_size == 0 {
+            return Err(BingoBoardFromStrError::NoLines);
+        }
+        if board_size * board_size > u128::BITS as usize {
+            return Err(BingoBoardFromStrError::BoardTooLarge(board_size));
+        }
+        if let Some((line_no, line)) = cells
+            .iter()
+            .enumerate()
+            .find(|(_, line)| line.len() != board_size)
+        {
+            return Err(BingoBoardFromStrError::LineCountOfElementsNotBoardSize(
+                line_no,
+                board_size,
+                line.clone(),
+            ));
+        }
+        Ok(Self {
+            cells,
+            marked: vec![vec![false; board_size]; board_size],
         })
     }
 }
@@ -231,13 +428,15 @@ impl FromStr for BingoBoard {
 pub enum BingoBoardFromStrError {
     #[error("Could not parse \"{0}\" to number ({1})")]
     Parse(String, #[source] ParseIntError),
-    #[error("Elements count of line no. {0} is not five ({1:?}) ({})")]
-    LineCountOfElementsNotFive(usize, Vec<u8>),
-    #[error("Count of lines is {0} and not five ({1:?})")]
-    LineCountNotFive(usize, Vec<[u8; 5]>),
+    #[error("Missing lines of bingo board")]
+    NoLines,
+    #[error("Bingo board size {0} is too large, at most 11 is supported")]
+    BoardTooLarge(usize),
+    #[error("Elements count of line no. {0} is not the board size {1} ({2:?})")]
+    LineCountOfElementsNotBoardSize(usize, usize, Vec<u8>),
 }
 
-#[derive(Eq, PartialEq, Copy, Clone)]
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
 pub enum BoardSelection {
     Winning,
     Loosing,
@@ -252,10 +451,80 @@ impl std::fmt::Display for BoardSelection {
     }
 }
 
+impl FromStr for BoardSelection {
+    type Err = BoardSelectionFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "winning" | "first" => Ok(Self::Winning),
+            "losing" | "loosing" | "last" => Ok(Self::Loosing),
+            _ => Err(BoardSelectionFromStrError::Unknown(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum BoardSelectionFromStrError {
+    #[error("Unknown board \"{0}\", expected winning, first, losing, loosing or last")]
+    Unknown(String),
+}
+
 #[cfg(test)]
 mod tests {
+    use rand::seq::SliceRandom;
+
     use super::*;
 
+    // the plain representation is kept to cross-check the bitmask one
+    impl BingoBoard {
+        fn mark(&mut self, number: u8) {
+            for y in 0..self.size() {
+                for x in 0..self.size() {
+                    if self.cells[y][x] == number {
+                        self.marked[y][x] = true;
+                    }
+                }
+            }
+        }
+
+        fn mark_returning_win(&mut self, number: u8) -> bool {
+            let mut won = false;
+            for y in 0..self.size() {
+                for x in 0..self.size() {
+                    if self.cells[y][x] == number {
+                        self.marked[y][x] = true;
+                        won |= self.marked[y].iter().all(|value| *value)
+                            || self.marked.iter().all(|line| line[x]);
+                    }
+                }
+            }
+            won
+        }
+
+        fn contains_bingo(&self) -> bool {
+            for column in 0..self.size() {
+                if self.marked.iter().all(|line| line[column]) {
+                    return true;
+                }
+            }
+            self.marked
+                .iter()
+                .any(|line| line.iter().all(|value| *value))
+        }
+
+        fn get_unmarked_cell_values(&self) -> Vec<u8> {
+            let mut output = Vec::new();
+            for y in 0..self.size() {
+                for x in 0..self.size() {
+                    if !self.marked[y][x] {
+                        output.push(self.cells[y][x]);
+                    }
+                }
+            }
+            output
+        }
+    }
+
     #[test]
     fn scores_of() {
         // given
@@ -283,14 +552,14 @@ mod tests {
         assert_eq!(
             bingo_board,
             Ok(BingoBoard {
-                cells: [
-                    [14, 21, 17, 24, 4],
-                    [10, 16, 15, 9, 19],
-                    [18, 8, 23, 26, 20],
-                    [22, 11, 13, 6, 5],
-                    [2, 0, 12, 3, 7]
+                cells: vec![
+                    vec![14, 21, 17, 24, 4],
+                    vec![10, 16, 15, 9, 19],
+                    vec![18, 8, 23, 26, 20],
+                    vec![22, 11, 13, 6, 5],
+                    vec![2, 0, 12, 3, 7]
                 ],
-                marked: [[false; 5]; 5]
+                marked: vec![vec![false; 5]; 5]
             })
         );
     }
@@ -298,15 +567,10 @@ mod tests {
     #[test]
     fn calculate_winning_bingo_board_scores_with_winning_should_return_188_24() {
         // given
-        let input = "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1\
-                            \r\n\r\n22 13 17 11  0\r\n 8  2 23  4 24\r\n21  9 14 16  7\
-                            \r\n 6 10  3 18  5\r\n 1 12 20 15 19\r\n\r\n 3 15  0  2 22\
-                            \r\n 9 18 13 17  5\r\n19  8  7 25 23\r\n20 11 10 24  4\r\n\
-                            14 21 16 12  6\r\n\r\n14 21 17 24  4\r\n10 16 15  9 19\r\n\
-                            18  8 23 26 20\r\n22 11 13  6  5\r\n 2  0 12  3  7";
+        let input = EXAMPLE;
 
         // when
-        let scores = calculate_winning_bingo_board_scores(input, BoardSelection::Winning);
+        let scores = calculate_winning_bingo_board_scores(input, BoardSelection::Winning, None);
 
         // then
         assert_eq!(scores, Ok(Scores::of(188, 24)));
@@ -315,17 +579,265 @@ mod tests {
     #[test]
     fn calculate_winning_bingo_board_scores_with_loosing_should_return_148_13() {
         // given
-        let input = "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1\
-                            \r\n\r\n22 13 17 11  0\r\n 8  2 23  4 24\r\n21  9 14 16  7\
-                            \r\n 6 10  3 18  5\r\n 1 12 20 15 19\r\n\r\n 3 15  0  2 22\
-                            \r\n 9 18 13 17  5\r\n19  8  7 25 23\r\n20 11 10 24  4\r\n\
-                            14 21 16 12  6\r\n\r\n14 21 17 24  4\r\n10 16 15  9 19\r\n\
-                            18  8 23 26 20\r\n22 11 13  6  5\r\n 2  0 12  3  7";
+        let input = EXAMPLE;
 
         // when
-        let scores = calculate_winning_bingo_board_scores(input, BoardSelection::Loosing);
+        let scores = calculate_winning_bingo_board_scores(input, BoardSelection::Loosing, None);
 
         // then
         assert_eq!(scores, Ok(Scores::of(148, 13)));
     }
+
+    #[test]
+    fn calculate_winning_bingo_board_scores_should_accept_extra_blank_lines() {
+        // given
+        let input = "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1\
+            \r\n\r\n\r\n22 13 17 11  0\r\n 8  2 23  4 24\r\n21  9 14 16  7\
+            \r\n 6 10  3 18  5\r\n 1 12 20 15 19\r\n   \r\n\r\n 3 15  0  2 22\
+            \r\n 9 18 13 17  5\r\n19  8  7 25 23\r\n20 11 10 24  4\r\n\
+            14 21 16 12  6\r\n\r\n \r\n\r\n14 21 17 24  4\r\n10 16 15  9 19\r\n\
+            18  8 23 26 20\r\n22 11 13  6  5\r\n 2  0 12  3  7\r\n\r\n";
+
+        // when
+        let winning_scores =
+            calculate_winning_bingo_board_scores(input, BoardSelection::Winning, None);
+        let loosing_scores =
+            calculate_winning_bingo_board_scores(input, BoardSelection::Loosing, None);
+
+        // then
+        assert_eq!(winning_scores, Ok(Scores::of(188, 24)));
+        assert_eq!(loosing_scores, Ok(Scores::of(148, 13)));
+    }
+
+    #[test]
+    fn calculate_winning_bingo_board_scores_should_reject_board_with_four_lines() {
+        // given
+        let input = "7,4,9\r\n\r\n22 13 17 11  0\r\n 8  2 23  4 24\r\n21  9 14 16  7\
+            \r\n 6 10  3 18  5\r\n\r\n 3 15  0  2 22\r\n 9 18 13 17  5\
+            \r\n19  8  7 25 23\r\n20 11 10 24  4\r\n14 21 16 12  6";
+
+        // when
+        let scores = calculate_winning_bingo_board_scores(input, BoardSelection::Winning, None);
+
+        // then
+        assert!(matches!(
+            scores,
+            Err(CalculateWinningBingoBoardScoresError::BoardFinishOrder(
+                BoardFinishOrderError::BingoBoardFromStr(
+                    _,
+                    BingoBoardFromStrError::LineCountOfElementsNotBoardSize(0, 4, _)
+                )
+            ))
+        ));
+    }
+
+    #[test]
+    fn board_finish_order_should_start_with_188_24_and_end_with_148_13() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let finish_order = board_finish_order(input);
+
+        // then
+        assert!(finish_order.is_ok());
+        let finish_order = finish_order.unwrap();
+        assert_eq!(finish_order.len(), 3);
+        assert_eq!(finish_order.first(), Some(&Scores::of(188, 24)));
+        assert_eq!(finish_order.last(), Some(&Scores::of(148, 13)));
+        assert_eq!(finish_order[2].sum_all_unmarked_numbers(), 148);
+        assert_eq!(finish_order[2].lastly_called_number(), 13);
+    }
+
+    #[test]
+    fn calculate_board_scores_and_finish_order_should_return_selected_scores_and_finish_order() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let scores_and_finish_order =
+            calculate_board_scores_and_finish_order(input, BoardSelection::Loosing, None);
+
+        // then
+        assert_eq!(
+            scores_and_finish_order,
+            Ok((Scores::of(148, 13), board_finish_order(input).unwrap()))
+        );
+    }
+
+    #[test]
+    fn board_finish_order_should_accept_wrapped_drawn_numbers() {
+        // given
+        let input = EXAMPLE.replacen("17,23,2,", "17,\r\n23,2\r\n", 1);
+
+        // when
+        let finish_order = board_finish_order(&input);
+
+        // then
+        assert_eq!(finish_order, board_finish_order(EXAMPLE));
+    }
+
+    #[test]
+    fn bingo_board_mark_returning_win_should_match_contains_bingo() {
+        // given
+        let drawn_numbers = [
+            7, 4, 9, 5, 11, 17, 23, 2, 0, 14, 21, 24, 10, 16, 13, 6, 15, 25, 12, 22, 18, 20, 8, 19,
+            3, 26, 1,
+        ];
+        let bingo_boards = [
+            "22 13 17 11  0\r\n 8  2 23  4 24\r\n21  9 14 16  7\r\n 6 10  3 18  5\r\n 1 12 20 15 19",
+            " 3 15  0  2 22\r\n 9 18 13 17  5\r\n19  8  7 25 23\r\n20 11 10 24  4\r\n14 21 16 12  6",
+            "14 21 17 24  4\r\n10 16 15  9 19\r\n18  8 23 26 20\r\n22 11 13  6  5\r\n 2  0 12  3  7",
+        ];
+
+        for bingo_board in bingo_boards {
+            let mut bingo_board = BingoBoard::from_str(bingo_board).unwrap();
+            let mut expected_board = bingo_board.clone();
+            for drawn_number in drawn_numbers {
+                // when
+                let won = bingo_board.mark_returning_win(drawn_number);
+
+                // then
+                expected_board.mark(drawn_number);
+                assert_eq!(bingo_board, expected_board);
+                assert_eq!(won, expected_board.contains_bingo());
+                if won {
+                    break;
+                }
+            }
+            assert!(bingo_board.contains_bingo());
+        }
+    }
+
+    #[test]
+    fn scores_should_be_ordered_by_final_score() {
+        // given
+        let winning_scores = Scores::of(188, 24);
+        let loosing_scores = Scores::of(148, 13);
+
+        // when
+        let ordering = winning_scores.cmp(&loosing_scores);
+
+        // then
+        assert_eq!(winning_scores.final_score(), 4512);
+        assert_eq!(loosing_scores.final_score(), 1924);
+        assert_eq!(ordering, Ordering::Greater);
+        assert!(Scores::of(2, 3) < Scores::of(3, 2));
+        assert_ne!(Scores::of(2, 3), Scores::of(3, 2));
+    }
+
+    #[test]
+    fn board_selection_from_str_should_accept_all_spellings() {
+        // given
+        let inputs = [
+            ("winning", Ok(BoardSelection::Winning)),
+            ("first", Ok(BoardSelection::Winning)),
+            ("losing", Ok(BoardSelection::Loosing)),
+            ("loosing", Ok(BoardSelection::Loosing)),
+            ("last", Ok(BoardSelection::Loosing)),
+            (
+                "middle",
+                Err(BoardSelectionFromStrError::Unknown("middle".to_string())),
+            ),
+        ];
+
+        for (input, expected_board_selection) in inputs {
+            // when
+            let board_selection = BoardSelection::from_str(input);
+
+            // then
+            assert_eq!(board_selection, expected_board_selection);
+        }
+    }
+
+    #[test]
+    fn calculate_winning_bingo_board_scores_with_three_by_three_boards() {
+        // given
+        let input = "5,1,9,4,6,2,3\r\n\r\n1 2 3\r\n4 5 6\r\n7 8 9\r\n\r\n7 3 5\r\n1 9 2\r\n8 4 6";
+
+        // when
+        let winning_scores =
+            calculate_winning_bingo_board_scores(input, BoardSelection::Winning, None);
+        let loosing_scores =
+            calculate_winning_bingo_board_scores(input, BoardSelection::Loosing, None);
+
+        // then
+        assert_eq!(winning_scores, Ok(Scores::of(2 + 3 + 7 + 8, 6)));
+        assert_eq!(loosing_scores, Ok(Scores::of(7 + 3 + 8, 2)));
+    }
+
+    #[test]
+    fn calculate_winning_bingo_board_scores_should_reject_boards_of_different_sizes() {
+        // given
+        let input = "1,2,3\r\n\r\n1 2 3\r\n4 5 6\r\n7 8 9\r\n\r\n1 2\r\n3 4";
+
+        // when
+        let scores = calculate_winning_bingo_board_scores(input, BoardSelection::Winning, None);
+
+        // then
+        assert_eq!(
+            scores,
+            Err(CalculateWinningBingoBoardScoresError::BoardFinishOrder(
+                BoardFinishOrderError::BoardSizeMismatch(1, 3, 2)
+            ))
+        );
+    }
+
+    #[test]
+    fn calculate_winning_bingo_board_scores_with_too_small_draw_limit_should_fail() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let scores = calculate_winning_bingo_board_scores(input, BoardSelection::Winning, Some(11));
+
+        // then
+        assert_eq!(
+            scores,
+            Err(CalculateWinningBingoBoardScoresError::NoBoardWonWithinLimit(11))
+        );
+        assert_eq!(
+            calculate_winning_bingo_board_scores(input, BoardSelection::Winning, Some(12)),
+            Ok(Scores::of(188, 24))
+        );
+    }
+
+    fn random_bingo_board(size: usize) -> (BingoBoard, Vec<u8>) {
+        let mut numbers = (0..100).collect::<Vec<u8>>();
+        numbers.shuffle(&mut rand::thread_rng());
+        let bingo_board = BingoBoard {
+            cells: numbers[..size * size]
+                .chunks(size)
+                .map(<[u8]>::to_vec)
+                .collect(),
+            marked: vec![vec![false; size]; size],
+        };
+        numbers.shuffle(&mut rand::thread_rng());
+        (bingo_board, numbers)
+    }
+
+    #[test]
+    fn bitmask_bingo_board_should_match_contains_bingo() {
+        for _ in 0..100 {
+            // given
+            let (mut bingo_board, drawn_numbers) = random_bingo_board(5);
+            let mut bitmask_bingo_board = BitmaskBingoBoard::from(&bingo_board);
+
+            for drawn_number in drawn_numbers {
+                // when
+                let won = bitmask_bingo_board.mark_returning_win(drawn_number);
+
+                // then
+                bingo_board.mark(drawn_number);
+                assert_eq!(won, bingo_board.contains_bingo());
+                assert_eq!(
+                    bitmask_bingo_board.get_unmarked_cell_values(),
+                    bingo_board.get_unmarked_cell_values()
+                );
+                if won {
+                    break;
+                }
+            }
+        }
+    }
 }
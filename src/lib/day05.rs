@@ -1,40 +1,73 @@
 use std::collections::HashMap;
 use std::num::ParseIntError;
 use std::str::FromStr;
+use std::time::Instant;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_puzzle_part_time_two,
+    clap_arg_repeat, clap_arg_time, part_from_matches, print_answer, print_elapsed_time,
+    read_input_contents, solve_repeatedly, Part, ReadFileContentsError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day05";
+pub const ABOUT: &str = "My solution for Day 5: Hydrothermal Venture";
+
+pub const EXAMPLE: &str = "0,9 -> 5,9\r\n8,0 -> 0,8\r\n9,4 -> 3,4\r\n2,2 -> 2,1\r\n7,0 -> 7,4\r\n\
+    6,4 -> 2,0\r\n0,9 -> 2,9\r\n3,4 -> 1,4\r\n0,0 -> 8,8\r\n5,5 -> 8,2";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 5: Hydrothermal Venture")
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day05-input"))
+        .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
         .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day05-input"),
+            Arg::with_name("max_overlap")
+                .long("max-overlap")
+                .help("prints the most covered point to stderr"),
         )
-        .arg(clap_arg_puzzle_part_time_two())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day05Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day05Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    let consider_diagonal_lines = matches!(
-        matches.value_of("puzzle_part").unwrap_or("two"),
-        "two" | "2"
+    let solving_start = Instant::now();
+    let include_diagonals = part_from_matches(matches) == Part::Two;
+    let count = if matches.is_present("max_overlap") {
+        let (count, (max_overlap, (x, y))) =
+            solve_repeatedly(matches, || -> Result<_, Day05Error> {
+                let vent_raster = rasterize_vent_lines(&file_contents, include_diagonals)?;
+                Ok((
+                    count_overlapping_points_in(&vent_raster),
+                    find_max_overlap(&vent_raster)?,
+                ))
+            })?;
+        eprintln!(
+            "The most covered point is {},{} with {} overlapping lines.",
+            x, y, max_overlap
+        );
+        count
+    } else {
+        solve_repeatedly(matches, || {
+            count_overlapping_points(&file_contents, include_diagonals)
+        })?
+    };
+    print_answer(
+        matches,
+        5,
+        if include_diagonals { 2 } else { 1 },
+        count,
+        format!("At {} points do at least two lines overlap.", count),
     );
-    let count =
-        calculate_count_of_line_overlapping_points(&file_contents, consider_diagonal_lines)?;
-    println!("At {} points do at least two lines overlap.", count);
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -42,26 +75,74 @@ pub fn handle(matches: &ArgMatches) -> Result<(), Day05Error> {
 pub enum Day05Error {
     #[error("Could not read file contents of \"{0:?}\" ({1})")]
     ReadFileContents(Option<String>, #[source] ReadFileContentsError),
-    #[error("Could not calculate count of line overlapping points ({0})")]
-    CalculateCountOfLineOverlappingPoints(#[from] CalculateCountOfLineOverlappingPointsError),
+    #[error("Could not count overlapping points ({0})")]
+    CountOverlappingPoints(#[from] CountOverlappingPointsError),
+    #[error("Could not find max overlap ({0})")]
+    FindMaxOverlap(#[from] FindMaxOverlapError),
+    #[error("Could not rasterize vent lines ({0})")]
+    RasterizeVentLines(#[from] RasterizeVentLinesError),
 }
 
-pub fn calculate_count_of_line_overlapping_points(
+pub fn count_overlapping_points(
     vent_lines_list: &str,
-    consider_diagonal_lines: bool,
-) -> Result<usize, CalculateCountOfLineOverlappingPointsError> {
-    Ok(parse_vent_lines(vent_lines_list)?
-        .into_iter()
-        .fold(Ok(HashMap::new()), |optional_field, line| {
-            optional_field.and_then(|field| draw_vent_line(field, line, consider_diagonal_lines))
-        })?
+    include_diagonals: bool,
+) -> Result<u128, CountOverlappingPointsError> {
+    Ok(count_overlapping_points_in(&rasterize_vent_lines(
+        vent_lines_list,
+        include_diagonals,
+    )?))
+}
+
+pub fn count_overlapping_points_in(vent_raster: &VentRaster) -> u128 {
+    vent_raster.0.values().filter(|count| **count >= 2).count() as u128
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum CountOverlappingPointsError {
+    #[error(transparent)]
+    RasterizeVentLines(#[from] RasterizeVentLinesError),
+}
+
+pub fn find_max_overlap(
+    vent_raster: &VentRaster,
+) -> Result<(u32, (i64, i64)), FindMaxOverlapError> {
+    vent_raster
+        .0
+        .iter()
+        .map(|((x, y), count)| (*count as u32, (*x as i64, *y as i64)))
+        // prefer the smallest coordinate when several points share the maximum
+        .max_by(|(count_a, position_a), (count_b, position_b)| {
+            count_a
+                .cmp(count_b)
+                .then_with(|| position_b.cmp(position_a))
+        })
+        .ok_or(FindMaxOverlapError::NoVentLines)
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum FindMaxOverlapError {
+    #[error("There are no vent lines covering any point")]
+    NoVentLines,
+}
+
+pub fn rasterize_vent_lines(
+    vent_lines_list: &str,
+    include_diagonals: bool,
+) -> Result<VentRaster, RasterizeVentLinesError> {
+    parse_vent_lines(vent_lines_list)?
         .into_iter()
-        .filter(|(_, count)| *count >= 2)
-        .count())
+        .try_fold(HashMap::new(), |field, line| {
+            draw_vent_line(field, line, include_diagonals)
+        })
+        .map(VentRaster)
+        .map_err(Into::into)
 }
 
+#[derive(Debug, Eq, PartialEq)]
+pub struct VentRaster(HashMap<(u16, u16), u128>);
+
 #[derive(Debug, Error, Eq, PartialEq)]
-pub enum CalculateCountOfLineOverlappingPointsError {
+pub enum RasterizeVentLinesError {
     #[error(transparent)]
     ParseVentLines(#[from] ParseVentLinesError),
     #[error(transparent)]
@@ -107,7 +188,7 @@ impl FromStr for VentLine {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let values: [&str; 5] = s
-            .split(|c| c == ',' || c == ' ')
+            .split([',', ' '])
             .collect::<Vec<&str>>()
             .try_into()
             .map_err(|_| LineFromStrError::WrongFormat(s.to_string()))?;
@@ -139,7 +220,7 @@ pub enum LineFromStrError {
 fn draw_vent_line(
     mut field: HashMap<(u16, u16), u128>,
     line: VentLine,
-    consider_diagonal_lines: bool,
+    include_diagonals: bool,
 ) -> Result<HashMap<(u16, u16), u128>, DrawVentLineError> {
     let max_x = line.x1.max(line.x2);
     let min_x = line.x1.min(line.x2);
@@ -159,7 +240,7 @@ fn draw_vent_line(
                 .and_modify(|cell| *cell += 1)
                 .or_insert(1u128);
         }
-    } else if consider_diagonal_lines {
+    } else if include_diagonals {
         let width = max_x - min_x;
         let height = max_y - min_y;
         if width == height {
@@ -189,7 +270,7 @@ fn draw_vent_line(
             return Err(DrawVentLineError::InvalidDiagonalLineFound(line));
         }
     } else {
-        println!("INFO: ignoring non-vertical/-horizontal {:?}", line);
+        eprintln!("INFO: ignoring non-vertical/-horizontal {:?}", line);
     }
     Ok(field)
 }
@@ -225,30 +306,64 @@ mod tests {
     }
 
     #[test]
-    fn calculate_count_of_line_overlapping_points_should_return_5() {
+    fn count_overlapping_points_should_return_5() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let count_of_overlapping_points = count_overlapping_points(input, false);
+
+        // then
+        assert_eq!(count_of_overlapping_points, Ok(5));
+    }
+
+    #[test]
+    fn count_overlapping_points_should_return_12() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let count_of_overlapping_points = count_overlapping_points(input, true);
+
+        // then
+        assert_eq!(count_of_overlapping_points, Ok(12));
+    }
+
+    #[test]
+    fn find_max_overlap_should_return_3_at_4_4() {
+        // given
+        let input = EXAMPLE;
+
+        let vent_raster = rasterize_vent_lines(input, true).unwrap();
+
+        // when
+        let max_overlap = find_max_overlap(&vent_raster);
+
+        // then
+        assert_eq!(max_overlap, Ok((3, (4, 4))));
+    }
+
+    #[test]
+    fn find_max_overlap_without_lines_should_fail() {
         // given
-        let input = "0,9 -> 5,9\r\n8,0 -> 0,8\r\n9,4 -> 3,4\r\n2,2 -> 2,1\r\n7,0 -> 7,4\r\n\
-                            6,4 -> 2,0\r\n0,9 -> 2,9\r\n3,4 -> 1,4\r\n0,0 -> 8,8\r\n5,5 -> 8,2";
+        let vent_raster = rasterize_vent_lines("", true).unwrap();
 
         // when
-        let count_of_line_overlapping_points =
-            calculate_count_of_line_overlapping_points(input, false);
+        let max_overlap = find_max_overlap(&vent_raster);
 
         // then
-        assert_eq!(count_of_line_overlapping_points, Ok(5));
+        assert_eq!(max_overlap, Err(FindMaxOverlapError::NoVentLines));
     }
 
     #[test]
-    fn calculate_count_of_line_overlapping_points_should_return_12() {
+    fn count_overlapping_points_without_lines_should_return_0() {
         // given
-        let input = "0,9 -> 5,9\r\n8,0 -> 0,8\r\n9,4 -> 3,4\r\n2,2 -> 2,1\r\n7,0 -> 7,4\r\n\
-                            6,4 -> 2,0\r\n0,9 -> 2,9\r\n3,4 -> 1,4\r\n0,0 -> 8,8\r\n5,5 -> 8,2";
+        let input = "";
 
         // when
-        let count_of_line_overlapping_points =
-            calculate_count_of_line_overlapping_points(input, true);
+        let count_of_overlapping_points = count_overlapping_points(input, true);
 
         // then
-        assert_eq!(count_of_line_overlapping_points, Ok(12));
+        assert_eq!(count_of_overlapping_points, Ok(0));
     }
 }
@@ -1,42 +1,101 @@
 use std::num::ParseIntError;
+use std::time::Instant;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_puzzle_part_time_two,
+    clap_arg_repeat, clap_arg_time, part_from_matches, print_answer, print_elapsed_time,
+    read_input_contents, solve_repeatedly, Part, ReadFileContentsError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day06";
+pub const ABOUT: &str = "My solution for Day 6: Lanternfish";
+
+pub const EXAMPLE: &str = "3,4,3,1,2\r\n";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 6: Lanternfish")
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day06-input"))
+        .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
         .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day06-input"),
+            Arg::with_name("days")
+                .short("d")
+                .long("days")
+                .value_name("DAYS")
+                .help("sets the count of days to simulate (defaults to 80 for part one and 256 for part two)"),
+        )
+        .arg(
+            Arg::with_name("history")
+                .long("history")
+                .help("prints the count of lanternfish after each day"),
         )
-        .arg(clap_arg_puzzle_part_time_two())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day06Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day06Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    let simulation_days = match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => 256,
-        _ => 80,
+    let solving_start = Instant::now();
+    let (part, default_simulation_days) = match part_from_matches(matches) {
+        Part::Two => (2, 256),
+        Part::One => (1, 80),
+    };
+    let simulation_days = match matches.value_of("days") {
+        Some(days) => days
+            .parse::<u32>()
+            .map_err(|error| Day06Error::ParseDays(days.to_string(), error))?,
+        None => default_simulation_days,
     };
-    let count_of_lanternfish = simulate_lanternfish(&file_contents, simulation_days)?
-        .iter()
-        .sum::<u128>();
-    println!(
-        "After {} days there are {} lanternfish.",
-        simulation_days, count_of_lanternfish
+    if matches.is_present("history") {
+        let history = solve_repeatedly(matches, || {
+            population_history(&file_contents, simulation_days)
+        })?;
+        print_answer(
+            matches,
+            6,
+            part,
+            history
+                .iter()
+                .map(u128::to_string)
+                .collect::<Vec<String>>()
+                .join(","),
+            history
+                .iter()
+                .enumerate()
+                .map(|(day, count_of_lanternfish)| {
+                    format!(
+                        "After {} days there are {} lanternfish.",
+                        day, count_of_lanternfish
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join("\n"),
+        );
+        print_elapsed_time(matches, solving_start);
+        return Ok(());
+    }
+    let count_of_lanternfish = solve_repeatedly(matches, || {
+        count_lanternfish_after(&file_contents, simulation_days)
+    })?;
+    print_answer(
+        matches,
+        6,
+        part,
+        count_of_lanternfish,
+        format!(
+            "After {} days there are {} lanternfish.",
+            simulation_days, count_of_lanternfish
+        ),
     );
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -44,7 +103,47 @@ pub fn handle(matches: &ArgMatches) -> Result<(), Day06Error> {
 pub enum Day06Error {
     #[error("Could not read file contents of \"{0:?}\" ({1})")]
     ReadFileContents(Option<String>, #[source] ReadFileContentsError),
-    #[error("Could not simulate lanternfish ({0})")]
+    #[error("Could not parse days \"{0}\" ({1})")]
+    ParseDays(String, #[source] ParseIntError),
+    #[error("Could not count lanternfish ({0})")]
+    CountLanternfishAfter(#[from] CountLanternfishAfterError),
+    #[error("Could not create population history ({0})")]
+    PopulationHistory(#[from] PopulationHistoryError),
+}
+
+pub fn count_lanternfish_after(
+    ages_of_nearby_lanternfish: &str,
+    simulation_days: u32,
+) -> Result<u128, CountLanternfishAfterError> {
+    Ok(
+        simulate_lanternfish(ages_of_nearby_lanternfish, simulation_days as u128)?
+            .iter()
+            .sum(),
+    )
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum CountLanternfishAfterError {
+    #[error(transparent)]
+    SimulateLanternfish(#[from] SimulateLanternfishError),
+}
+
+pub fn population_history(
+    ages_of_nearby_lanternfish: &str,
+    simulation_days: u32,
+) -> Result<Vec<u128>, PopulationHistoryError> {
+    let mut lanternfish = simulate_lanternfish(ages_of_nearby_lanternfish, 0)?;
+    let mut history = vec![lanternfish.iter().sum()];
+    for _ in 1..=simulation_days {
+        simulate_lanternfish_day(&mut lanternfish);
+        history.push(lanternfish.iter().sum());
+    }
+    Ok(history)
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum PopulationHistoryError {
+    #[error(transparent)]
     SimulateLanternfish(#[from] SimulateLanternfishError),
 }
 
@@ -74,17 +173,21 @@ pub fn simulate_lanternfish(
             lanternfish
         });
     for _ in 1..=simulation_days {
-        let reincarnating_lanternfish = lanternfish[0];
-        for lanternfish_age in 1..=8 {
-            let aging_lanternfish = lanternfish[lanternfish_age];
-            lanternfish[lanternfish_age - 1] = aging_lanternfish;
-        }
-        lanternfish[6] += reincarnating_lanternfish;
-        lanternfish[8] = reincarnating_lanternfish;
+        simulate_lanternfish_day(&mut lanternfish);
     }
     Ok(lanternfish)
 }
 
+fn simulate_lanternfish_day(lanternfish: &mut [u128; 9]) {
+    let reincarnating_lanternfish = lanternfish[0];
+    for lanternfish_age in 1..=8 {
+        let aging_lanternfish = lanternfish[lanternfish_age];
+        lanternfish[lanternfish_age - 1] = aging_lanternfish;
+    }
+    lanternfish[6] += reincarnating_lanternfish;
+    lanternfish[8] = reincarnating_lanternfish;
+}
+
 #[derive(Debug, Error, Eq, PartialEq)]
 pub enum SimulateLanternfishError {
     #[error("Could not parse lantern fish age \"{0}\" ({1})")]
@@ -100,7 +203,7 @@ mod tests {
     #[test]
     fn simulate_lanternfish_should_return_5_elements() {
         // given
-        let input = "3,4,3,1,2\r\n";
+        let input = EXAMPLE;
 
         // when
         let lanternfish = simulate_lanternfish(input, 1);
@@ -113,7 +216,7 @@ mod tests {
     #[test]
     fn simulate_lanternfish_should_return_6_elements() {
         // given
-        let input = "3,4,3,1,2\r\n";
+        let input = EXAMPLE;
 
         // when
         let lanternfish = simulate_lanternfish(input, 2);
@@ -126,7 +229,7 @@ mod tests {
     #[test]
     fn simulate_lanternfish_should_return_5934_elements() {
         // given
-        let input = "3,4,3,1,2\r\n";
+        let input = EXAMPLE;
 
         // when
         let lanternfish = simulate_lanternfish(input, 80);
@@ -139,7 +242,7 @@ mod tests {
     #[test]
     fn simulate_lanternfish_should_return_26984457539_elements() {
         // given
-        let input = "3,4,3,1,2\r\n";
+        let input = EXAMPLE;
 
         // when
         let lanternfish = simulate_lanternfish(input, 256);
@@ -148,4 +251,43 @@ mod tests {
         assert!(lanternfish.is_ok());
         assert_eq!(lanternfish.unwrap().iter().sum::<u128>(), 26984457539);
     }
+
+    #[test]
+    fn count_lanternfish_after_80_days_should_return_5934() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let count_of_lanternfish = count_lanternfish_after(input, 80);
+
+        // then
+        assert_eq!(count_of_lanternfish, Ok(5934));
+    }
+
+    #[test]
+    fn count_lanternfish_after_256_days_should_return_26984457539() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let count_of_lanternfish = count_lanternfish_after(input, 256);
+
+        // then
+        assert_eq!(count_of_lanternfish, Ok(26984457539));
+    }
+
+    #[test]
+    fn population_history_should_return_first_days_of_sample() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let history = population_history(input, 18);
+
+        // then
+        let history = history.unwrap();
+        assert_eq!(history.len(), 19);
+        assert_eq!(history[..6], [5, 5, 6, 7, 9, 10]);
+        assert_eq!(history[18], 26);
+    }
 }
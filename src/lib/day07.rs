@@ -1,44 +1,81 @@
 use std::num::ParseIntError;
 use std::ops::Add;
+use std::str::FromStr;
+use std::time::Instant;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_puzzle_part_time_two,
+    clap_arg_repeat, clap_arg_time, part_from_matches, print_answer, print_elapsed_time,
+    read_input_contents, solve_repeatedly, Part, ReadFileContentsError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day07";
+pub const ABOUT: &str = "My solution for Day 7: The Treachery of Whales";
+
+pub const EXAMPLE: &str = "16,1,2,0,4,2,7,1,2,14\r\n";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 7: The Treachery of Whales")
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day07-input"))
+        .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
         .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day07-input"),
+            Arg::with_name("fuel_model")
+                .long("fuel-model")
+                .value_name("FUEL_MODEL")
+                .help("overrides the fuel calculation of the part (linear, exponential or triangular)"),
+        )
+        .arg(
+            Arg::with_name("brute_force").long("brute-force").help(
+                "tries every position between the outermost crabs instead of the median or mean",
+            ),
         )
-        .arg(clap_arg_puzzle_part_time_two())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day07Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day07Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    let needed_fuel_calculation = match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => NeededFuelCalculation::Exponential,
-        _ => NeededFuelCalculation::Linear,
+    let solving_start = Instant::now();
+    let (part, needed_fuel_calculation) = match part_from_matches(matches) {
+        Part::Two => (2, NeededFuelCalculation::Exponential),
+        Part::One => (1, NeededFuelCalculation::Linear),
     };
-    let (position, usage) = determine_horizontal_position_with_least_fuel_usage(
-        &file_contents,
-        needed_fuel_calculation,
-    )?;
-    println!(
-        "Horizontal position {} has with {} fuel usage the least usage with {:?} fuel usage",
-        position, usage, needed_fuel_calculation
+    let needed_fuel_calculation = match matches.value_of("fuel_model") {
+        Some(fuel_model) => NeededFuelCalculation::from_str(fuel_model)?,
+        None => needed_fuel_calculation,
+    };
+    let (position, usage) = if matches.is_present("brute_force") {
+        solve_repeatedly(matches, || {
+            determine_horizontal_position_with_least_fuel_usage(
+                &file_contents,
+                needed_fuel_calculation,
+            )
+        })?
+    } else {
+        solve_repeatedly(matches, || {
+            determine_position_fast(&file_contents, needed_fuel_calculation)
+        })?
+    };
+    print_answer(
+        matches,
+        7,
+        part,
+        usage,
+        format!(
+            "Horizontal position {} has with {} fuel usage the least usage with {:?} fuel usage",
+            position, usage, needed_fuel_calculation
+        ),
     );
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -46,10 +83,14 @@ pub fn handle(matches: &ArgMatches) -> Result<(), Day07Error> {
 pub enum Day07Error {
     #[error("Could not read file contents of \"{0:?}\" ({1})")]
     ReadFileContents(Option<String>, #[source] ReadFileContentsError),
+    #[error("Could not parse fuel model ({0})")]
+    NeededFuelCalculationFromStr(#[from] NeededFuelCalculationFromStrError),
     #[error("Could not determine horizontal position with least fuel usage ({0})")]
     DetermineHorizontalPositionWithLeastFuelUsage(
         #[from] DetermineHorizontalPositionWithLeastFuelUsageError,
     ),
+    #[error("Could not determine position with least fuel usage ({0})")]
+    DeterminePositionFast(#[from] DeterminePositionFastError),
 }
 
 pub fn determine_horizontal_position_with_least_fuel_usage(
@@ -83,6 +124,62 @@ pub fn determine_horizontal_position_with_least_fuel_usage(
         .ok_or(DetermineHorizontalPositionWithLeastFuelUsageError::MissingHorizontalCrabPositions)
 }
 
+pub fn determine_position_fast(
+    horizontal_crab_positions: &str,
+    needed_fuel_calculation: NeededFuelCalculation,
+) -> Result<(HorizontalPosition, FuelUsage), DeterminePositionFastError> {
+    let mut horizontal_positions = parse_horizontal_crab_positions(horizontal_crab_positions)?;
+    if horizontal_positions.is_empty() {
+        return Err(DeterminePositionFastError::MissingHorizontalCrabPositions);
+    }
+    let candidates = match needed_fuel_calculation {
+        NeededFuelCalculation::Linear => {
+            horizontal_positions.sort();
+            vec![horizontal_positions[(horizontal_positions.len() - 1) / 2]]
+        }
+        NeededFuelCalculation::Exponential => {
+            let mean = horizontal_positions
+                .iter()
+                .map(HorizontalPosition::value)
+                .sum::<u128>()
+                / horizontal_positions.len() as u128;
+            vec![
+                HorizontalPosition::of(mean),
+                HorizontalPosition::of(mean + 1),
+            ]
+        }
+    };
+    candidates
+        .into_iter()
+        .map(|target_position| {
+            let fuel_usage = horizontal_positions
+                .iter()
+                .map(|start_position| {
+                    target_position.needed_fuel_to(start_position, needed_fuel_calculation)
+                })
+                .fold(FuelUsage(0), FuelUsage::add);
+            (target_position, fuel_usage)
+        })
+        .reduce(
+            |(target_position_a, fuel_usage_a), (target_position_b, fuel_usage_b)| {
+                if fuel_usage_a <= fuel_usage_b {
+                    (target_position_a, fuel_usage_a)
+                } else {
+                    (target_position_b, fuel_usage_b)
+                }
+            },
+        )
+        .ok_or(DeterminePositionFastError::MissingHorizontalCrabPositions)
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum DeterminePositionFastError {
+    #[error(transparent)]
+    ParseHorizontalCrabPositions(#[from] ParseHorizontalCrabPositionsError),
+    #[error("Missing horizontal crab positions")]
+    MissingHorizontalCrabPositions,
+}
+
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
 pub struct HorizontalPosition(u128);
 
@@ -101,14 +198,20 @@ impl HorizontalPosition {
         needed_fuel_calculation: NeededFuelCalculation,
     ) -> FuelUsage {
         match needed_fuel_calculation {
-            NeededFuelCalculation::Linear => FuelUsage(
-                self.0
-                    .checked_sub(other.0)
-                    .unwrap_or_else(|| other.0 - self.0),
-            ),
+            NeededFuelCalculation::Linear => FuelUsage(self.0.abs_diff(other.0)),
             NeededFuelCalculation::Exponential => {
-                let (min, max) = (self.0.min(other.0), self.0.max(other.0));
-                FuelUsage((min..=max).map(|val| val - min).sum())
+                let distance = self.0.abs_diff(other.0);
+                // d * (d + 1) / 2, halving whichever factor is even, saturating like FuelUsage
+                let triangular_number = if distance.is_multiple_of(2) {
+                    distance
+                        .checked_add(1)
+                        .and_then(|successor| (distance / 2).checked_mul(successor))
+                } else {
+                    distance
+                        .checked_add(1)
+                        .and_then(|successor| distance.checked_mul(successor / 2))
+                };
+                FuelUsage(triangular_number.unwrap_or(u128::MAX))
             }
         }
     }
@@ -126,12 +229,30 @@ impl std::fmt::Display for HorizontalPosition {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
 pub enum NeededFuelCalculation {
     Linear,
     Exponential,
 }
 
+impl FromStr for NeededFuelCalculation {
+    type Err = NeededFuelCalculationFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "linear" => Ok(Self::Linear),
+            "exponential" | "triangular" => Ok(Self::Exponential),
+            _ => Err(NeededFuelCalculationFromStrError::Unknown(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum NeededFuelCalculationFromStrError {
+    #[error("Unknown fuel model \"{0}\", expected linear, exponential or triangular")]
+    Unknown(String),
+}
+
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
 pub struct FuelUsage(u128);
 
@@ -166,25 +287,18 @@ pub enum DetermineHorizontalPositionWithLeastFuelUsageError {
 fn parse_horizontal_crab_positions(
     horizontal_crab_positions: &str,
 ) -> Result<Vec<HorizontalPosition>, ParseHorizontalCrabPositionsError> {
-    Ok(horizontal_crab_positions
-        .lines()
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            line.split(',')
-                .map(|element| {
-                    element
-                        .parse::<u128>()
-                        .map(HorizontalPosition::of)
-                        .map_err(|error| {
-                            ParseHorizontalCrabPositionsError::Parse(element.to_string(), error)
-                        })
+    horizontal_crab_positions
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|element| !element.is_empty())
+        .map(|element| {
+            element
+                .parse::<u128>()
+                .map(HorizontalPosition::of)
+                .map_err(|error| {
+                    ParseHorizontalCrabPositionsError::Parse(element.to_string(), error)
                 })
-                .collect::<Result<Vec<HorizontalPosition>, ParseHorizontalCrabPositionsError>>()
         })
-        .collect::<Result<Vec<Vec<HorizontalPosition>>, ParseHorizontalCrabPositionsError>>()?
-        .into_iter()
-        .flatten()
-        .collect::<Vec<HorizontalPosition>>())
+        .collect()
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
@@ -266,7 +380,7 @@ mod tests {
     #[test]
     fn determine_horizontal_position_with_least_fuel_usage_should_return_2_37() {
         // given
-        let input = "16,1,2,0,4,2,7,1,2,14\r\n";
+        let input = EXAMPLE;
 
         // when
         let result = determine_horizontal_position_with_least_fuel_usage(
@@ -281,7 +395,7 @@ mod tests {
     #[test]
     fn determine_horizontal_position_with_least_fuel_usage_should_return_5_168() {
         // given
-        let input = "16,1,2,0,4,2,7,1,2,14\r\n";
+        let input = EXAMPLE;
 
         // when
         let result = determine_horizontal_position_with_least_fuel_usage(
@@ -292,4 +406,165 @@ mod tests {
         // then
         assert_eq!(result, Ok((HorizontalPosition::of(5), FuelUsage(168))))
     }
+
+    #[test]
+    fn determine_position_fast_should_agree_with_brute_force_on_sample() {
+        // given
+        let input = EXAMPLE;
+
+        for needed_fuel_calculation in [
+            NeededFuelCalculation::Linear,
+            NeededFuelCalculation::Exponential,
+        ] {
+            // when
+            let fast = determine_position_fast(input, needed_fuel_calculation);
+
+            // then
+            let brute_force =
+                determine_horizontal_position_with_least_fuel_usage(input, needed_fuel_calculation);
+            assert_eq!(fast.map_err(|_| ()), brute_force.map_err(|_| ()));
+        }
+    }
+
+    #[test]
+    fn determine_position_fast_should_fail_without_positions() {
+        // given
+        let input = "\r\n";
+
+        // when
+        let result = determine_position_fast(input, NeededFuelCalculation::Linear);
+
+        // then
+        assert_eq!(
+            result,
+            Err(DeterminePositionFastError::MissingHorizontalCrabPositions)
+        );
+    }
+
+    #[test]
+    fn parse_horizontal_crab_positions_should_accept_comma_separated() {
+        // given
+        let input = "16,1,2\r\n";
+
+        // when
+        let positions = parse_horizontal_crab_positions(input);
+
+        // then
+        assert_eq!(
+            positions,
+            Ok(vec![
+                HorizontalPosition::of(16),
+                HorizontalPosition::of(1),
+                HorizontalPosition::of(2)
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_horizontal_crab_positions_should_accept_space_separated() {
+        // given
+        let input = "16 1  2\r\n";
+
+        // when
+        let positions = parse_horizontal_crab_positions(input);
+
+        // then
+        assert_eq!(
+            positions,
+            Ok(vec![
+                HorizontalPosition::of(16),
+                HorizontalPosition::of(1),
+                HorizontalPosition::of(2)
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_horizontal_crab_positions_should_accept_line_separated() {
+        // given
+        let input = "16\r\n1\r\n\r\n2\r\n";
+
+        // when
+        let positions = parse_horizontal_crab_positions(input);
+
+        // then
+        assert_eq!(
+            positions,
+            Ok(vec![
+                HorizontalPosition::of(16),
+                HorizontalPosition::of(1),
+                HorizontalPosition::of(2)
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_horizontal_crab_positions_should_report_invalid_element() {
+        // given
+        let input = "16, x,2";
+
+        // when
+        let positions = parse_horizontal_crab_positions(input);
+
+        // then
+        assert!(matches!(
+            positions,
+            Err(ParseHorizontalCrabPositionsError::Parse(element, _)) if element == "x"
+        ));
+    }
+
+    #[test]
+    fn needed_fuel_calculation_from_str() {
+        // when
+        let linear = NeededFuelCalculation::from_str("linear");
+        let exponential = NeededFuelCalculation::from_str("exponential");
+        let triangular = NeededFuelCalculation::from_str("triangular");
+        let unknown = NeededFuelCalculation::from_str("quadratic");
+
+        // then
+        assert_eq!(linear, Ok(NeededFuelCalculation::Linear));
+        assert_eq!(exponential, Ok(NeededFuelCalculation::Exponential));
+        assert_eq!(triangular, Ok(NeededFuelCalculation::Exponential));
+        assert_eq!(
+            unknown,
+            Err(NeededFuelCalculationFromStrError::Unknown(
+                "quadratic".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn needed_fuel_to_exponential_should_equal_summation() {
+        // given
+        let start = HorizontalPosition::of(5);
+
+        for target in [0, 1, 2, 5, 16, 100, 1234] {
+            // when
+            let needed_fuel = start.needed_fuel_to(
+                &HorizontalPosition::of(target),
+                NeededFuelCalculation::Exponential,
+            );
+
+            // then
+            let (min, max) = (target.min(5), target.max(5));
+            assert_eq!(
+                needed_fuel,
+                FuelUsage((min..=max).map(|val| val - min).sum())
+            );
+        }
+        assert_eq!(
+            HorizontalPosition::of(16).needed_fuel_to(
+                &HorizontalPosition::of(5),
+                NeededFuelCalculation::Exponential
+            ),
+            FuelUsage(66)
+        );
+        assert_eq!(
+            HorizontalPosition::of(0).needed_fuel_to(
+                &HorizontalPosition::of(u128::MAX),
+                NeededFuelCalculation::Exponential
+            ),
+            FuelUsage(u128::MAX)
+        );
+    }
 }
@@ -1,49 +1,138 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::Instant;
+
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_puzzle_part_time_two,
+    clap_arg_repeat, clap_arg_time, part_from_matches, print_answer, print_elapsed_time,
+    read_input_contents, solve_repeatedly, Part, ReadFileContentsError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day08";
+pub const ABOUT: &str = "My solution for Day 8: Seven Segment Search";
+
+pub const EXAMPLE: &str = "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe \
+    cefdb cefbgd gcbe\r\nedbfga begcd cbg gc gcadebf fbgde acbgfd abcde \
+    gfcbed gfec | fcgedb cgb dgebacf gc\r\nfgaebd cg bdaec gdafb agbcfd \
+    gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg\r\nfbegcd cbd adcefb \
+    dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb\r\n\
+    aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf \
+    egdcabf bgf bfgea\r\nfgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg \
+    bafgc acf | gebdcfa ecba ca fadegcb\r\ndbcfg fgd bdegcaf fgec aegbdf \
+    ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe\r\nbdfegc \
+    cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba \
+    cbgef\r\negadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | \
+    gbdfcae bgc cg cgb\r\ngcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge \
+    fdbac fegbdc | fgae cfgab fg bagce";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 8: Seven Segment Search")
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day08-input"))
+        .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
         .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day08-input"),
+            Arg::with_name("digit_frequencies")
+                .long("digit-frequencies")
+                .help("prints how often each output digit decoded by the selected part appears to stderr"),
+        )
+        .arg(
+            Arg::with_name("signals")
+                .long("signals")
+                .help("prints every output value as decoded by the selected part to stderr"),
+        )
+        .arg(
+            Arg::with_name("power")
+                .long("power")
+                .value_name("POWER")
+                .help("overrides the decoding power of part one (half, simple, 1, full, deduce or 2)"),
         )
-        .arg(clap_arg_puzzle_part_time_two())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day08Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day08Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => {
-            let signals = decode_mixed_up_signals(&file_contents, DecodingPower::Full)?;
-            println!(
-                "The sum of all decoded digits is {}.",
-                signals
-                    .iter()
-                    .map(Signal::as_number)
-                    .map(|v| v as u128)
-                    .sum::<u128>()
+    let solving_start = Instant::now();
+    let optional_decoding_power = matches
+        .value_of("power")
+        .map(DecodingPower::from_str)
+        .transpose()?;
+    let lists_signals = matches.is_present("signals") || matches.is_present("digit_frequencies");
+    let print_signals = |signals: &[Signal]| {
+        if matches.is_present("signals") {
+            for signal in signals {
+                eprintln!("{} ({} decoded)", signal, signal.count_decoded());
+            }
+        }
+        if matches.is_present("digit_frequencies") {
+            for (digit, frequency) in frequencies_of_digits(signals).iter().enumerate() {
+                eprintln!("{}: {}", digit, frequency);
+            }
+        }
+    };
+    match part_from_matches(matches) {
+        Part::Two if optional_decoding_power.is_some() => {
+            return Err(Day08Error::DecodingPowerOnlyInPartOne)
+        }
+        Part::Two => {
+            let signals = solve_repeatedly(matches, || {
+                decode_mixed_up_signals(&file_contents, DecodingPower::Full)
+            })?;
+            print_signals(&signals);
+            let sum_of_decoded_digits = signals
+                .iter()
+                .map(Signal::as_number)
+                .map(|v| v as u128)
+                .sum::<u128>();
+            print_answer(
+                matches,
+                8,
+                2,
+                sum_of_decoded_digits,
+                format!(
+                    "The sum of all decoded digits is {}.",
+                    sum_of_decoded_digits
+                ),
             );
         }
-        _ => {
-            let signals = decode_mixed_up_signals(&file_contents, DecodingPower::Half)?;
-            println!(
-                "The digits 1, 4, 7, 8 appear {} times.",
+        Part::One => {
+            let count_of_decoded_digits = if optional_decoding_power.is_some() || lists_signals {
+                let decoding_power = optional_decoding_power.unwrap_or(DecodingPower::Half);
+                let signals = solve_repeatedly(matches, || {
+                    decode_mixed_up_signals(&file_contents, decoding_power)
+                })?;
+                print_signals(&signals);
                 signals.iter().map(Signal::count_decoded).sum::<usize>()
+            } else {
+                solve_repeatedly(matches, || count_easy_digits(&file_contents))?
+            };
+            print_answer(
+                matches,
+                8,
+                1,
+                count_of_decoded_digits,
+                match optional_decoding_power {
+                    Some(decoding_power) => format!(
+                        "With {:?} decoding power {} digits could be decoded.",
+                        decoding_power, count_of_decoded_digits
+                    ),
+                    None => format!(
+                        "The digits 1, 4, 7, 8 appear {} times.",
+                        count_of_decoded_digits
+                    ),
+                },
             );
         }
     };
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -53,12 +142,72 @@ pub enum Day08Error {
     ReadFileContents(Option<String>, #[source] ReadFileContentsError),
     #[error("Could not decode mixed up signals ({0})")]
     DecodeMixedUpSignals(#[from] DecodeMixedUpSignalsError),
+    #[error("Could not parse decoding power ({0})")]
+    DecodingPowerFromStr(#[from] DecodingPowerFromStrError),
+    #[error("The decoding power can only be overridden for part one")]
+    DecodingPowerOnlyInPartOne,
 }
 
 pub fn decode_mixed_up_signals(
     signals_with_notes: &str,
     decoding_power: DecodingPower,
-) -> Result<Vec<Signal>, DecodeMixedUpSignalsError> {
+) -> Result<Vec<Signal<'_>>, DecodeMixedUpSignalsError> {
+    signals_with_notes
+        .lines()
+        .map(|line| match decoding_power {
+            DecodingPower::Half => parse_entry(line).and_then(map_line_a),
+            DecodingPower::Full => decode_entry(line),
+        })
+        .collect::<Result<Vec<Signal>, DecodeMixedUpSignalsError>>()
+}
+
+pub fn count_easy_digits(signals_with_notes: &str) -> Result<usize, DecodeMixedUpSignalsError> {
+    signals_with_notes
+        .lines()
+        .map(|line| {
+            parse_entry(line).and_then(|(_, output_values)| {
+                output_values
+                    .iter()
+                    .map(|output_value| match output_value.len() {
+                        2 | 3 | 4 | 7 => Ok(1),
+                        5 | 6 => Ok(0),
+                        a => Err(DecodeMixedUpSignalsError::EntryHasUnexpectedLength(
+                            output_value.to_string(),
+                            a,
+                        )),
+                    })
+                    .sum::<Result<usize, DecodeMixedUpSignalsError>>()
+            })
+        })
+        .sum()
+}
+
+pub fn digit_frequencies(
+    signals_with_notes: &str,
+) -> Result<[u128; 10], DecodeMixedUpSignalsError> {
+    Ok(frequencies_of_digits(&decode_mixed_up_signals(
+        signals_with_notes,
+        DecodingPower::Full,
+    )?))
+}
+
+fn frequencies_of_digits(signals: &[Signal]) -> [u128; 10] {
+    let mut frequencies = [0; 10];
+    for signal in signals {
+        for signal_number in [signal.first, signal.second, signal.third, signal.fourth] {
+            if let SignalNumber::Decoded(number) = signal_number {
+                frequencies[number as usize] += 1;
+            }
+        }
+    }
+    frequencies
+}
+
+pub fn decode_entry(line: &str) -> Result<Signal<'_>, DecodeMixedUpSignalsError> {
+    parse_entry(line).and_then(map_line_b)
+}
+
+fn parse_entry(line: &str) -> Result<([&str; 10], [&str; 4]), DecodeMixedUpSignalsError> {
     fn extract_entries<const C: usize>(
         element_entries: &str,
     ) -> Result<[&str; C], DecodeMixedUpSignalsError> {
@@ -75,227 +224,226 @@ pub fn decode_mixed_up_signals(
             })
     }
 
-    let lines = signals_with_notes
-        .lines()
-        .map(|line| {
-            line.split('|')
-                .collect::<Vec<&str>>()
-                .try_into()
-                .map_err(|vec: Vec<&str>| {
-                    DecodeMixedUpSignalsError::LineHasUnexpectedCountOfVerticalBars(
-                        line.to_string(),
-                        vec.len(),
-                    )
-                })
-                .map(|elements: [&str; 2]| {
-                    extract_entries(elements[0])
-                        .and_then(|a| extract_entries(elements[1]).map(|b| (a, b)))
-                })
+    let elements: [&str; 2] =
+        line.split('|')
+            .collect::<Vec<&str>>()
+            .try_into()
+            .map_err(|vec: Vec<&str>| {
+                DecodeMixedUpSignalsError::LineHasUnexpectedCountOfVerticalBars(
+                    line.to_string(),
+                    vec.len(),
+                )
+            })?;
+    Ok((extract_entries(elements[0])?, extract_entries(elements[1])?))
+}
+
+fn map_line_a<'a>(
+    line: ([&str; 10], [&'a str; 4]),
+) -> Result<Signal<'a>, DecodeMixedUpSignalsError> {
+    line.1
+        .iter()
+        .map(|entry| match entry.len() {
+            2 => Ok(SignalNumber::Decoded(1)),
+            3 => Ok(SignalNumber::Decoded(7)),
+            4 => Ok(SignalNumber::Decoded(4)),
+            5 => Ok(SignalNumber::Coded(entry)), // 2 & 3 & 5
+            6 => Ok(SignalNumber::Coded(entry)), // 0 & 6 & 9
+            7 => Ok(SignalNumber::Decoded(8)),
+            a => Err(DecodeMixedUpSignalsError::EntryHasUnexpectedLength(
+                entry.to_string(),
+                a,
+            )),
         })
-        .collect::<Result<
-            Vec<Result<([&str; 10], [&str; 4]), DecodeMixedUpSignalsError>>,
-            DecodeMixedUpSignalsError,
-        >>()?
-        .into_iter()
-        .collect::<Result<Vec<([&str; 10], [&str; 4])>, DecodeMixedUpSignalsError>>()?;
+        .collect::<Result<Vec<SignalNumber>, DecodeMixedUpSignalsError>>()
+        .map(|signal_numbers| Signal {
+            first: signal_numbers[0],
+            second: signal_numbers[1],
+            third: signal_numbers[2],
+            fourth: signal_numbers[3],
+        })
+}
 
-    fn map_line_a<'a>(
-        line: ([&str; 10], [&'a str; 4]),
-    ) -> Result<Signal<'a>, DecodeMixedUpSignalsError> {
-        line.1
+fn map_line_b<'a>(
+    line: ([&str; 10], [&'a str; 4]),
+) -> Result<Signal<'a>, DecodeMixedUpSignalsError> {
+    /*
+      0000
+     1    2
+     1    2
+      3333
+     4    5
+     4    5
+      6666
+    */
+    let mut notes: [Vec<char>; 7] = [
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+    ];
+
+    // 1) remove noted characters from input and then note remaining input characters into the notes
+    let indices_per_entry_length_1 = [
+        (2, vec![2, 5]),
+        (3, vec![0, 2, 5]),
+        (4, vec![1, 2, 3, 5]),
+        (7, vec![0, 1, 2, 3, 4, 5, 6]),
+    ];
+    for (entry_length, indices) in indices_per_entry_length_1 {
+        let (retained_chars, removed_chars): (Vec<char>, Vec<char>) = line
+            .0
             .iter()
-            .map(|entry| match entry.len() {
-                2 => Ok(SignalNumber::Decoded(1)),
-                3 => Ok(SignalNumber::Decoded(7)),
-                4 => Ok(SignalNumber::Decoded(4)),
-                5 => Ok(SignalNumber::Coded(entry)), // 2 & 3 & 5
-                6 => Ok(SignalNumber::Coded(entry)), // 0 & 6 & 9
-                7 => Ok(SignalNumber::Decoded(8)),
-                a => Err(DecodeMixedUpSignalsError::EntryHasUnexpectedLength(
-                    entry.to_string(),
-                    a,
-                )),
-            })
-            .collect::<Result<Vec<SignalNumber>, DecodeMixedUpSignalsError>>()
-            .map(|signal_numbers| Signal {
-                first: signal_numbers[0],
-                second: signal_numbers[1],
-                third: signal_numbers[2],
-                fourth: signal_numbers[3],
-            })
-    }
-
-    fn map_line_b<'a>(
-        line: ([&str; 10], [&'a str; 4]),
-    ) -> Result<Signal<'a>, DecodeMixedUpSignalsError> {
-        /*
-          0000
-         1    2
-         1    2
-          3333
-         4    5
-         4    5
-          6666
-        */
-        let mut notes: [Vec<char>; 7] = [
-            Vec::new(),
-            Vec::new(),
-            Vec::new(),
-            Vec::new(),
-            Vec::new(),
-            Vec::new(),
-            Vec::new(),
-        ];
-
-        // 1) remove noted characters from input and then note remaining input characters into the notes
-        let indices_per_entry_length_1 = [
-            (2, vec![2, 5]),
-            (3, vec![0, 2, 5]),
-            (4, vec![1, 2, 3, 5]),
-            (7, vec![0, 1, 2, 3, 4, 5, 6]),
-        ];
-        for (entry_length, indices) in indices_per_entry_length_1 {
-            let (retained_chars, removed_chars): (Vec<char>, Vec<char>) = line
-                .0
+            .find(|entry| entry.len() == entry_length)
+            .ok_or(DecodeMixedUpSignalsError::MissingEntryWithLength(
+                entry_length,
+            ))?
+            .chars()
+            .partition(|character| !notes.iter().any(|segment| segment.contains(character)));
+        for index in indices {
+            if !removed_chars
                 .iter()
-                .find(|entry| entry.len() == entry_length)
-                .ok_or(DecodeMixedUpSignalsError::MissingEntryWithLength(
-                    entry_length,
-                ))?
-                .chars()
-                .partition(|character| !notes.iter().any(|segment| segment.contains(character)));
-            for index in indices {
-                if !removed_chars
-                    .iter()
-                    .any(|character| notes[index].contains(character))
-                {
-                    notes[index].extend(&retained_chars);
-                }
+                .any(|character| notes[index].contains(character))
+            {
+                notes[index].extend(&retained_chars);
             }
         }
+    }
 
-        // 2) remove from note segments chars which are not in the entries; if then segment only has one char, remove that from every other segment
-        let indices_per_entry_length_2 = [(6, vec![0, 1, 5, 6]), (5, vec![0, 3, 6])];
-        for (entry_length, indices) in indices_per_entry_length_2 {
-            let entries_with_entry_length = line
-                .0
-                .iter()
-                .filter(|entry| entry.len() == entry_length)
-                .collect::<Vec<&&str>>();
-            for entry in entries_with_entry_length {
-                for index in &indices {
-                    notes[*index].retain(|character| entry.contains(*character));
-                    if notes[*index].len() == 1 {
-                        let character_to_remove = notes[*index][0];
-                        notes
-                            .iter_mut()
-                            .enumerate()
-                            .filter(|(inner_index, _)| *index != *inner_index)
-                            .for_each(|(_, segment)| {
-                                segment.retain(|character| *character != character_to_remove)
-                            });
-                    }
+    // 2) remove from note segments chars which are not in the entries; if then segment only has one char, remove that from every other segment
+    let indices_per_entry_length_2 = [(6, vec![0, 1, 5, 6]), (5, vec![0, 3, 6])];
+    for (entry_length, indices) in indices_per_entry_length_2 {
+        let entries_with_entry_length = line
+            .0
+            .iter()
+            .filter(|entry| entry.len() == entry_length)
+            .collect::<Vec<&&str>>();
+        for entry in entries_with_entry_length {
+            for index in &indices {
+                notes[*index].retain(|character| entry.contains(*character));
+                if notes[*index].len() == 1 {
+                    let character_to_remove = notes[*index][0];
+                    notes
+                        .iter_mut()
+                        .enumerate()
+                        .filter(|(inner_index, _)| *index != *inner_index)
+                        .for_each(|(_, segment)| {
+                            segment.retain(|character| *character != character_to_remove)
+                        });
                 }
             }
         }
+    }
 
-        // 3) only one character per segment should remain
-        let notes: [char; 7] = notes
-            .into_iter()
-            .enumerate()
-            .map(|(index, segment)| {
-                if segment.len() == 1 {
-                    Ok(segment[0])
-                } else {
-                    let mut line_str = line.0.join(" ");
-                    line_str.push_str(" | ");
-                    line_str.push_str(&line.1.join(" "));
-                    Err(
-                        DecodeMixedUpSignalsError::DeducedSegmentHasUnexpectedPossibilities(
-                            line_str, index, segment,
-                        ),
-                    )
-                }
-            })
-            .collect::<Result<Vec<char>, DecodeMixedUpSignalsError>>()?
-            .try_into()
-            .unwrap();
-
-        // 4) convert second element
-        line.1
-            .iter()
-            .map(|entry| -> Result<SignalNumber, DecodeMixedUpSignalsError> {
-                let numbers_indices = [
-                    vec![0, 1, 2, 4, 5, 6],
-                    vec![2, 5],
-                    vec![0, 2, 3, 4, 6],
-                    vec![0, 2, 3, 5, 6],
-                    vec![1, 2, 3, 5],
-                    vec![0, 1, 3, 5, 6],
-                    vec![0, 1, 3, 4, 5, 6],
-                    vec![0, 2, 5],
-                    vec![0, 1, 2, 3, 4, 5, 6],
-                    vec![0, 1, 2, 3, 5, 6],
-                ];
-                for (index, number_indices) in numbers_indices.iter().enumerate() {
-                    if number_indices
-                        .iter()
-                        .all(|internal_index| entry.contains(notes[*internal_index]))
-                        && entry.len() == numbers_indices[index].len()
-                    {
-                        return Ok(SignalNumber::Decoded(index as u8));
-                    }
-                }
-                Ok(SignalNumber::Coded(entry))
-            })
-            .collect::<Result<Vec<SignalNumber>, DecodeMixedUpSignalsError>>()?
-            .into_iter()
-            .fold(
-                (None, None, None, None),
-                |(mut a, mut b, mut c, mut s), next| {
-                    match a {
-                        None => a = Some(next),
-                        Some(ua) => match b {
-                            None => b = Some(next),
-                            Some(ub) => match c {
-                                None => c = Some(next),
-                                Some(uc) => {
-                                    s = Some(Signal {
-                                        first: ua,
-                                        second: ub,
-                                        third: uc,
-                                        fourth: next,
-                                    })
-                                }
-                            },
-                        },
-                    }
-                    (a, b, c, s)
-                },
-            )
-            .3
-            .ok_or_else(|| {
+    // 3) only one character per segment should remain
+    let notes: [char; 7] = notes
+        .into_iter()
+        .enumerate()
+        .map(|(index, segment)| {
+            if segment.len() == 1 {
+                Ok(segment[0])
+            } else {
                 let mut line_str = line.0.join(" ");
                 line_str.push_str(" | ");
                 line_str.push_str(&line.1.join(" "));
-                DecodeMixedUpSignalsError::NotEnoughEntriesInSecondElement(line_str)
-            })
-    }
-
-    lines
+                Err(
+                    DecodeMixedUpSignalsError::DeducedSegmentHasUnexpectedPossibilities(
+                        line_str, index, segment,
+                    ),
+                )
+            }
+        })
+        .collect::<Result<Vec<char>, DecodeMixedUpSignalsError>>()?
+        .try_into()
+        .unwrap();
+
+    // 4) convert second element
+    line.1
+        .iter()
+        .map(|entry| -> Result<SignalNumber, DecodeMixedUpSignalsError> {
+            let numbers_indices = [
+                vec![0, 1, 2, 4, 5, 6],
+                vec![2, 5],
+                vec![0, 2, 3, 4, 6],
+                vec![0, 2, 3, 5, 6],
+                vec![1, 2, 3, 5],
+                vec![0, 1, 3, 5, 6],
+                vec![0, 1, 3, 4, 5, 6],
+                vec![0, 2, 5],
+                vec![0, 1, 2, 3, 4, 5, 6],
+                vec![0, 1, 2, 3, 5, 6],
+            ];
+            for (index, number_indices) in numbers_indices.iter().enumerate() {
+                if number_indices
+                    .iter()
+                    .all(|internal_index| entry.contains(notes[*internal_index]))
+                    && entry.len() == numbers_indices[index].len()
+                {
+                    return Ok(SignalNumber::Decoded(index as u8));
+                }
+            }
+            Ok(SignalNumber::Coded(entry))
+        })
+        .collect::<Result<Vec<SignalNumber>, DecodeMixedUpSignalsError>>()?
         .into_iter()
-        .map(|line| match decoding_power {
-            DecodingPower::Half => map_line_a(line),
-            DecodingPower::Full => map_line_b(line),
+        .fold(
+            (None, None, None, None),
+            |(mut a, mut b, mut c, mut s), next| {
+                match a {
+                    None => a = Some(next),
+                    Some(ua) => match b {
+                        None => b = Some(next),
+                        Some(ub) => match c {
+                            None => c = Some(next),
+                            Some(uc) => {
+                                s = Some(Signal {
+                                    first: ua,
+                                    second: ub,
+                                    third: uc,
+                                    fourth: next,
+                                })
+                            }
+                        },
+                    },
+                }
+                (a, b, c, s)
+            },
+        )
+        .3
+        .ok_or_else(|| {
+            let mut line_str = line.0.join(" ");
+            line_str.push_str(" | ");
+            line_str.push_str(&line.1.join(" "));
+            DecodeMixedUpSignalsError::NotEnoughEntriesInSecondElement(line_str)
         })
-        .collect::<Result<Vec<Signal>, DecodeMixedUpSignalsError>>()
 }
 
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
 pub enum DecodingPower {
     Half,
     Full,
 }
 
+impl FromStr for DecodingPower {
+    type Err = DecodingPowerFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "half" | "simple" | "1" => Ok(Self::Half),
+            "full" | "deduce" | "2" => Ok(Self::Full),
+            _ => Err(DecodingPowerFromStrError::Unknown(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum DecodingPowerFromStrError {
+    #[error("Unknown decoding power \"{0}\", expected half, simple, 1, full, deduce or 2")]
+    Unknown(String),
+}
+
 #[derive(Eq, PartialEq, Copy, Clone)]
 pub struct Signal<'a> {
     first: SignalNumber<'a>,
@@ -330,6 +478,16 @@ impl<'a> Signal<'a> {
     }
 }
 
+impl<'a> Display for Signal<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}{}{}",
+            self.first, self.second, self.third, self.fourth
+        )
+    }
+}
+
 #[derive(Eq, PartialEq, Copy, Clone)]
 pub enum SignalNumber<'a> {
     Decoded(u8),
@@ -345,6 +503,15 @@ impl<'a> SignalNumber<'a> {
     }
 }
 
+impl<'a> Display for SignalNumber<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignalNumber::Decoded(number) => write!(f, "{}", number),
+            SignalNumber::Coded(_) => write!(f, "?"),
+        }
+    }
+}
+
 #[derive(Debug, Error, Eq, PartialEq)]
 pub enum DecodeMixedUpSignalsError {
     #[error("Line \"{0}\" has unexpected count of vertical bars of {1} (expected 2)")]
@@ -416,6 +583,23 @@ mod tests {
         signal.as_number();
     }
 
+    #[test]
+    fn signal_display_should_show_question_mark_for_coded_entries() {
+        // given
+        let signal = Signal {
+            first: SignalNumber::Decoded(5),
+            second: SignalNumber::Coded("bgc"),
+            third: SignalNumber::Decoded(3),
+            fourth: SignalNumber::Decoded(2),
+        };
+
+        // when
+        let displayed = signal.to_string();
+
+        // then
+        assert_eq!(displayed, "5?32");
+    }
+
     #[test]
     fn signal_number_unwrap() {
         // given
@@ -441,19 +625,7 @@ mod tests {
     #[test]
     fn decode_mixed_up_signals_should_return_26_instances() {
         // given
-        let input = "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe \
-                            cefdb cefbgd gcbe\r\nedbfga begcd cbg gc gcadebf fbgde acbgfd abcde \
-                            gfcbed gfec | fcgedb cgb dgebacf gc\r\nfgaebd cg bdaec gdafb agbcfd \
-                            gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg\r\nfbegcd cbd adcefb \
-                            dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb\r\n\
-                            aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf \
-                            egdcabf bgf bfgea\r\nfgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg \
-                            bafgc acf | gebdcfa ecba ca fadegcb\r\ndbcfg fgd bdegcaf fgec aegbdf \
-                            ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe\r\nbdfegc \
-                            cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba \
-                            cbgef\r\negadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | \
-                            gbdfcae bgc cg cgb\r\ngcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge \
-                            fdbac fegbdc | fgae cfgab fg bagce";
+        let input = EXAMPLE;
 
         // when
         let signals = decode_mixed_up_signals(input, DecodingPower::Half);
@@ -473,19 +645,7 @@ mod tests {
     #[test]
     fn decode_mixed_up_signals_should_return_sum_of_61229() {
         // given
-        let input = "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe \
-                            cefdb cefbgd gcbe\r\nedbfga begcd cbg gc gcadebf fbgde acbgfd abcde \
-                            gfcbed gfec | fcgedb cgb dgebacf gc\r\nfgaebd cg bdaec gdafb agbcfd \
-                            gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg\r\nfbegcd cbd adcefb \
-                            dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb\r\n\
-                            aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf \
-                            egdcabf bgf bfgea\r\nfgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg \
-                            bafgc acf | gebdcfa ecba ca fadegcb\r\ndbcfg fgd bdegcaf fgec aegbdf \
-                            ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe\r\nbdfegc \
-                            cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba \
-                            cbgef\r\negadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | \
-                            gbdfcae bgc cg cgb\r\ngcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge \
-                            fdbac fegbdc | fgae cfgab fg bagce";
+        let input = EXAMPLE;
 
         // when
         let signals = decode_mixed_up_signals(input, DecodingPower::Full);
@@ -506,4 +666,97 @@ mod tests {
             61229
         );
     }
+
+    #[test]
+    fn decode_entry_should_return_5353() {
+        // given
+        let input =
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+
+        // when
+        let signal = decode_entry(input);
+
+        // then
+        assert_eq!(signal.map(|signal| signal.as_number()), Ok(5353));
+    }
+
+    #[test]
+    fn digit_frequencies_should_count_all_40_output_digits() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let frequencies = digit_frequencies(input);
+
+        // then
+        assert!(frequencies.is_ok());
+        let frequencies = frequencies.unwrap();
+        assert_eq!(frequencies.iter().sum::<u128>(), 40);
+        assert_eq!(
+            frequencies[1] + frequencies[4] + frequencies[7] + frequencies[8],
+            26
+        );
+    }
+
+    #[test]
+    fn count_easy_digits_should_reject_entry_with_unexpected_length() {
+        // given
+        let input =
+            "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | f cefdb cefbgd gcbe";
+
+        // when
+        let count_of_easy_digits = count_easy_digits(input);
+
+        // then
+        assert_eq!(
+            count_of_easy_digits,
+            Err(DecodeMixedUpSignalsError::EntryHasUnexpectedLength(
+                "f".to_string(),
+                1
+            ))
+        );
+    }
+
+    #[test]
+    fn count_easy_digits_should_return_26() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let count_of_easy_digits = count_easy_digits(input);
+
+        // then
+        assert_eq!(count_of_easy_digits, Ok(26));
+        assert_eq!(
+            count_of_easy_digits.ok(),
+            decode_mixed_up_signals(input, DecodingPower::Half)
+                .map(|signals| signals.iter().map(Signal::count_decoded).sum())
+                .ok()
+        );
+    }
+
+    #[test]
+    fn decoding_power_from_str_should_accept_all_spellings() {
+        // given
+        let inputs = [
+            ("half", Ok(DecodingPower::Half)),
+            ("simple", Ok(DecodingPower::Half)),
+            ("1", Ok(DecodingPower::Half)),
+            ("full", Ok(DecodingPower::Full)),
+            ("deduce", Ok(DecodingPower::Full)),
+            ("2", Ok(DecodingPower::Full)),
+            (
+                "quarter",
+                Err(DecodingPowerFromStrError::Unknown("quarter".to_string())),
+            ),
+        ];
+
+        for (input, expected_decoding_power) in inputs {
+            // when
+            let decoding_power = DecodingPower::from_str(input);
+
+            // then
+            assert_eq!(decoding_power, expected_decoding_power);
+        }
+    }
 }
@@ -1,49 +1,83 @@
-use std::collections::HashMap;
-use std::num::ParseIntError;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_puzzle_part_time_two,
+    clap_arg_repeat, clap_arg_time, neighbors4, neighbors8, parse_digit_grid, part_from_matches,
+    print_answer, print_elapsed_time, read_input_contents, solve_repeatedly, GridParseError, Part,
+    ReadFileContentsError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day09";
+pub const ABOUT: &str = "My solution for Day 9: Smoke Basin";
+
+pub const EXAMPLE: &str = "2199943210\r\n3987894921\r\n9856789892\r\n8767896789\r\n9899965678";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 9: Smoke Basin")
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day09-input"))
+        .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
         .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day09-input"),
+            Arg::with_name("connectivity")
+                .long("connectivity")
+                .value_name("CONNECTIVITY")
+                .help("selects whether diagonal locations are adjacent")
+                .possible_values(&["four", "eight"])
+                .default_value("four"),
         )
-        .arg(clap_arg_puzzle_part_time_two())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day09Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day09Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => {
-            let product_of_sizes_of_three_largest_basins =
-                calculate_product_of_sizes_of_three_largest_basins(&file_contents)?;
-            println!(
-                "The product of the sizes of the three largest basins is {}.",
-                product_of_sizes_of_three_largest_basins
+    let solving_start = Instant::now();
+    let connectivity = match matches.value_of("connectivity").unwrap_or("four") {
+        "eight" => Connectivity::EightWay,
+        _ => Connectivity::FourWay,
+    };
+    match part_from_matches(matches) {
+        Part::Two => {
+            let product_of_sizes_of_three_largest_basins = solve_repeatedly(matches, || {
+                calculate_product_of_sizes_of_three_largest_basins(&file_contents, connectivity)
+            })?;
+            print_answer(
+                matches,
+                9,
+                2,
+                product_of_sizes_of_three_largest_basins,
+                format!(
+                    "The product of the sizes of the three largest basins is {}.",
+                    product_of_sizes_of_three_largest_basins
+                ),
             );
         }
-        _ => {
-            let summed_risk_levels = sum_risk_levels_of_lowest_points(&file_contents)?;
-            println!(
-                "The sum of risk levels of lowest points is {}.",
-                summed_risk_levels
+        Part::One => {
+            let summed_risk_levels = solve_repeatedly(matches, || {
+                sum_risk_levels_of_lowest_points(&file_contents, connectivity)
+            })?;
+            print_answer(
+                matches,
+                9,
+                1,
+                summed_risk_levels,
+                format!(
+                    "The sum of risk levels of lowest points is {}.",
+                    summed_risk_levels
+                ),
             );
         }
     };
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -61,82 +95,108 @@ pub enum Day09Error {
 
 pub fn sum_risk_levels_of_lowest_points(
     height_map: &str,
+    connectivity: Connectivity,
 ) -> Result<u128, SumRiskLevelsOfLowestPointsError> {
-    Ok(find_low_points(&parse_height_map(height_map)?)
+    Ok(low_points(height_map, connectivity)?
         .into_iter()
-        .map(|low_point| low_point.value + 1)
+        .map(|(_, _, height)| height + 1)
         .map(|risk_level| risk_level as u128)
         .sum::<u128>())
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
 pub enum SumRiskLevelsOfLowestPointsError {
+    #[error(transparent)]
+    LowPoints(#[from] LowPointsError),
+}
+
+pub fn low_points(
+    height_map: &str,
+    connectivity: Connectivity,
+) -> Result<Vec<(usize, usize, u8)>, LowPointsError> {
+    Ok(
+        find_low_points(&parse_digit_grid(height_map)?, connectivity)
+            .into_iter()
+            .map(|low_point| (low_point.position.x, low_point.position.y, low_point.value))
+            .collect(),
+    )
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum LowPointsError {
     #[error("Could not parse height map ({0})")]
-    ParseHeightMap(#[from] ParseHeightMapError),
+    ParseHeightMap(#[from] GridParseError),
 }
 
 pub fn calculate_product_of_sizes_of_three_largest_basins(
     height_map: &str,
+    connectivity: Connectivity,
 ) -> Result<u128, CalculateProductOfSizesOfThreeLargestBasinsError> {
-    let height_map = parse_height_map(height_map)?;
-    let low_points = find_low_points(&height_map);
-    let mut basins = calculate_basins(&low_points, &height_map);
-    basins.sort_by(|a, b| a.size.cmp(&b.size).reverse());
-    if basins.len() >= 3 {
-        Ok(basins[0].size as u128 * basins[1].size as u128 * basins[2].size as u128)
+    let mut basin_sizes = basins(height_map, connectivity)?
+        .iter()
+        .map(Vec::len)
+        .collect::<Vec<usize>>();
+    basin_sizes.sort_by(|a, b| a.cmp(b).reverse());
+    if basin_sizes.len() >= 3 {
+        Ok(basin_sizes[0] as u128 * basin_sizes[1] as u128 * basin_sizes[2] as u128)
     } else {
-        Err(CalculateProductOfSizesOfThreeLargestBasinsError::MissingBasins(basins.len()))
+        Err(CalculateProductOfSizesOfThreeLargestBasinsError::MissingBasins(basin_sizes.len()))
     }
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
 pub enum CalculateProductOfSizesOfThreeLargestBasinsError {
-    #[error("Could not parse height map ({0})")]
-    ParseHeightMap(#[from] ParseHeightMapError),
+    #[error(transparent)]
+    Basins(#[from] BasinsError),
     #[error("Not enough basins found (only {0})")]
     MissingBasins(usize),
 }
 
-fn parse_height_map(height_map: &str) -> Result<Vec<Vec<u8>>, ParseHeightMapError> {
-    height_map
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|char| {
-                    char.to_string()
-                        .parse::<u8>()
-                        .map_err(|error| ParseHeightMapError::ParseInt(char, error))
-                })
-                .collect::<Result<Vec<u8>, ParseHeightMapError>>()
+pub fn basins(
+    height_map: &str,
+    connectivity: Connectivity,
+) -> Result<Vec<Vec<(usize, usize)>>, BasinsError> {
+    let height_map = parse_digit_grid(height_map)?;
+    let low_points = find_low_points(&height_map, connectivity);
+    Ok(calculate_basins(&low_points, &height_map, connectivity)
+        .into_iter()
+        .map(|basin| {
+            basin
+                .positions
+                .into_iter()
+                .map(|position| (position.x, position.y))
+                .collect()
         })
-        .collect::<Result<Vec<Vec<u8>>, ParseHeightMapError>>()
+        .collect())
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
-pub enum ParseHeightMapError {
-    #[error("Could not parse '{0}' ({1})")]
-    ParseInt(char, ParseIntError),
+pub enum BasinsError {
+    #[error("Could not parse height map ({0})")]
+    ParseHeightMap(#[from] GridParseError),
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub enum Connectivity {
+    #[default]
+    FourWay,
+    EightWay,
 }
 
-fn find_low_points(height_map: &[Vec<u8>]) -> Vec<LowPoint> {
+fn find_low_points(height_map: &[Vec<u8>], connectivity: Connectivity) -> Vec<LowPoint> {
     (0..height_map.len())
-        .into_iter()
-        .flat_map(|y| (0..height_map[y].len()).into_iter().map(move |x| (x, y)))
-        .filter_map(|(x, y)| {
-            let lower_than_west = x == 0 || height_map[y][x] < height_map[y][x - 1];
-            let lower_than_north = y == 0 || height_map[y][x] < height_map[y - 1][x];
-            let lower_than_east =
-                (x + 1) >= height_map[y].len() || height_map[y][x] < height_map[y][x + 1];
-            let lower_than_south =
-                (y + 1) >= height_map.len() || height_map[y][x] < height_map[y + 1][x];
-            if lower_than_west && lower_than_north && lower_than_east && lower_than_south {
-                Some(LowPoint {
-                    position: Position { x, y },
-                    value: height_map[y][x],
+        .flat_map(|y| (0..height_map[y].len()).map(move |x| Position { x, y }))
+        .filter(|position| {
+            position
+                .neighbours(height_map, connectivity)
+                .into_iter()
+                .all(|neighbour| {
+                    height_map[position.y][position.x] < height_map[neighbour.y][neighbour.x]
                 })
-            } else {
-                None
-            }
+        })
+        .map(|position| LowPoint {
+            position,
+            value: height_map[position.y][position.x],
         })
         .collect()
 }
@@ -154,43 +214,31 @@ struct Position {
 }
 
 impl Position {
-    fn north(&self) -> Self {
-        Self {
-            x: self.x,
-            y: self.y.saturating_sub(1),
-        }
-    }
-
-    fn south(&self, max: usize) -> Self {
-        let y = self.y.saturating_add(1);
-        Self {
-            x: self.x,
-            y: if y > max { self.y } else { y },
-        }
-    }
-
-    fn east(&self, max: usize) -> Self {
-        let x = self.x.saturating_add(1);
-        Self {
-            x: if x > max { self.x } else { x },
-            y: self.y,
-        }
-    }
-
-    fn west(&self) -> Self {
-        Self {
-            x: self.x.saturating_sub(1),
-            y: self.y,
+    fn neighbours(&self, height_map: &[Vec<u8>], connectivity: Connectivity) -> Vec<Self> {
+        let (width, height) = (height_map.first().map_or(0, Vec::len), height_map.len());
+        let to_position = |(x, y)| Self { x, y };
+        match connectivity {
+            Connectivity::FourWay => neighbors4(self.x, self.y, width, height)
+                .map(to_position)
+                .collect(),
+            Connectivity::EightWay => neighbors8(self.x, self.y, width, height)
+                .map(to_position)
+                .collect(),
         }
     }
 }
 
-fn calculate_basins(low_points: &[LowPoint], height_map: &[Vec<u8>]) -> Vec<Basin> {
+fn calculate_basins(
+    low_points: &[LowPoint],
+    height_map: &[Vec<u8>],
+    connectivity: Connectivity,
+) -> Vec<Basin> {
     low_points
         .iter()
         .map(|low_point| -> Basin {
             let mut positions_to_visit = vec![low_point.position];
             let mut positions_visited = Vec::new();
+            let mut positions_seen = HashSet::from([low_point.position]);
 
             let mut directions = HashMap::new();
 
@@ -201,9 +249,8 @@ fn calculate_basins(low_points: &[LowPoint], height_map: &[Vec<u8>]) -> Vec<Basi
                 let mut add_next_positions =
                     |current_position: Position, next_position: Position| {
                         if next_position != current_position
-                            && !(positions_to_visit.contains(&next_position)
-                                || positions_visited.contains(&next_position))
                             && height_map[next_position.y][next_position.x] < 9
+                            && positions_seen.insert(next_position)
                         {
                             positions_to_visit.push(next_position);
                             directions
@@ -213,30 +260,23 @@ fn calculate_basins(low_points: &[LowPoint], height_map: &[Vec<u8>]) -> Vec<Basi
                         }
                     };
 
-                add_next_positions(current_position, current_position.north());
-                add_next_positions(
-                    current_position,
-                    current_position.east(height_map[current_position.y].len().saturating_sub(1)),
-                );
-                add_next_positions(
-                    current_position,
-                    current_position.south(height_map.len().saturating_sub(1)),
-                );
-                add_next_positions(current_position, current_position.west());
+                for next_position in current_position.neighbours(height_map, connectivity) {
+                    add_next_positions(current_position, next_position);
+                }
             }
 
             Basin {
                 low_point: *low_point,
-                size: positions_visited.len(),
+                positions: positions_visited,
             }
         })
         .collect()
 }
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone)]
 struct Basin {
     low_point: LowPoint,
-    size: usize,
+    positions: Vec<Position>,
 }
 
 #[cfg(test)]
@@ -246,10 +286,10 @@ mod tests {
     #[test]
     fn sum_risk_levels_should_return_15() {
         // given
-        let input = "2199943210\r\n3987894921\r\n9856789892\r\n8767896789\r\n9899965678";
+        let input = EXAMPLE;
 
         // when
-        let summed_risk_levels = sum_risk_levels_of_lowest_points(input);
+        let summed_risk_levels = sum_risk_levels_of_lowest_points(input, Connectivity::FourWay);
 
         // then
         assert_eq!(summed_risk_levels, Ok(15));
@@ -258,13 +298,60 @@ mod tests {
     #[test]
     fn calculate_product_of_sizes_of_three_largest_basins_should_return_1134() {
         // given
-        let input = "2199943210\r\n3987894921\r\n9856789892\r\n8767896789\r\n9899965678";
+        let input = EXAMPLE;
 
         // when
         let product_of_sizes_of_three_largest_basins =
-            calculate_product_of_sizes_of_three_largest_basins(&input);
+            calculate_product_of_sizes_of_three_largest_basins(input, Connectivity::FourWay);
 
         // then
         assert_eq!(product_of_sizes_of_three_largest_basins, Ok(1134));
     }
+
+    #[test]
+    fn low_points_should_return_four_low_points() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let low_points = low_points(input, Connectivity::FourWay);
+
+        // then
+        assert_eq!(
+            low_points,
+            Ok(vec![(1, 0, 1), (9, 0, 0), (2, 2, 5), (6, 4, 5)])
+        );
+    }
+
+    #[test]
+    fn basins_should_return_members_of_each_basin() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let basins = basins(input, Connectivity::FourWay);
+
+        // then
+        let basins = basins.unwrap();
+        let mut basin_sizes = basins.iter().map(Vec::len).collect::<Vec<usize>>();
+        basin_sizes.sort_unstable();
+        assert_eq!(basin_sizes, vec![3, 9, 9, 14]);
+        assert!(basins[0].contains(&(0, 0)));
+        assert!(basins[0].contains(&(1, 0)));
+        assert!(basins[0].contains(&(0, 1)));
+    }
+
+    #[test]
+    fn basins_should_merge_diagonal_neighbours_with_eight_way_connectivity() {
+        // given
+        let input = "09\r\n91";
+
+        // when
+        let four_way_basins = basins(input, Connectivity::FourWay);
+        let eight_way_basins = basins(input, Connectivity::EightWay);
+
+        // then
+        assert_eq!(four_way_basins, Ok(vec![vec![(0, 0)], vec![(1, 1)]]));
+        assert_eq!(eight_way_basins, Ok(vec![vec![(0, 0), (1, 1)]]));
+    }
 }
@@ -1,46 +1,108 @@
+use std::time::Instant;
+
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_puzzle_part_time_two,
+    clap_arg_repeat, clap_arg_time, part_from_matches, print_answer, print_elapsed_time,
+    read_input_contents, solve_repeatedly, Part, ReadFileContentsError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day10";
+pub const ABOUT: &str = "My solution for Day 10: Syntax Scoring";
+
+pub const EXAMPLE: &str = "[({(<(())[]>[[{[]{<()<>>\r\n[(()[<>])]({[<{<<[]>>(\r\n\
+    {([(<{}[<>[]}>{[]{[(<()>\r\n(((({<>}<{<{<>}{[]{[]{}\r\n\
+    [[<[([]))<([[{}[[()]]]\r\n[{[{({}]{}}([{[{{{}}([]\r\n\
+    {<[[]]>}<{[{[{[]{()[[[]\r\n[<(<(<(<{}))><([]([]()\r\n\
+    <{([([[(<>()){}]>(<<{{\r\n<{([{{}}[<[[[<>{}]]]>[]]";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 10: Syntax Scoring")
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day10-input"))
+        .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
         .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day10-input"),
+            Arg::with_name("corruption_report")
+                .long("corruption-report")
+                .help("prints every corrupted line with its illegal character and score"),
+        )
+        .arg(
+            Arg::with_name("completions")
+                .long("completions")
+                .help("prints the closing characters completing every incomplete line"),
         )
-        .arg(clap_arg_puzzle_part_time_two())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day10Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day10Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => {
-            let middle_autocomplete_score = calculate_middle_autocomplete_score(&file_contents)?;
-            println!(
-                "The middle autocomplete score is: {}.",
-                middle_autocomplete_score
+    let solving_start = Instant::now();
+    let mut optional_corruption_report = None;
+    let mut optional_completions = None;
+    let mut report_on = |parsed_lines: &[ParsedLineResult]| -> Result<(), Day10Error> {
+        if matches.is_present("corruption_report") {
+            optional_corruption_report = Some(corruption_report_of(parsed_lines)?);
+        }
+        if matches.is_present("completions") {
+            optional_completions = Some(completions_of(parsed_lines)?);
+        }
+        Ok(())
+    };
+    match part_from_matches(matches) {
+        Part::Two => {
+            let middle_autocomplete_score = solve_repeatedly(matches, || {
+                let parsed_lines = parse_navigation_subsystem_lines(&file_contents);
+                report_on(&parsed_lines)?;
+                Ok::<_, Day10Error>(middle_autocomplete_score_of(&parsed_lines)?)
+            })?;
+            print_answer(
+                matches,
+                10,
+                2,
+                middle_autocomplete_score,
+                format!(
+                    "The middle autocomplete score is: {}.",
+                    middle_autocomplete_score
+                ),
             );
         }
-        _ => {
-            let total_syntax_error_score = calculate_total_syntax_error_score(&file_contents)?;
-            println!(
-                "The total syntax error score is: {}.",
-                total_syntax_error_score
+        Part::One => {
+            let total_syntax_error_score = solve_repeatedly(matches, || {
+                let parsed_lines = parse_navigation_subsystem_lines(&file_contents);
+                report_on(&parsed_lines)?;
+                Ok::<_, Day10Error>(total_syntax_error_score_of(&parsed_lines)?)
+            })?;
+            print_answer(
+                matches,
+                10,
+                1,
+                total_syntax_error_score,
+                format!(
+                    "The total syntax error score is: {}.",
+                    total_syntax_error_score
+                ),
             );
         }
     };
+    for (lines_index, illegal_character, score) in optional_corruption_report.unwrap_or_default() {
+        eprintln!(
+            "Line {} is corrupted by '{}' (score {}).",
+            lines_index, illegal_character, score
+        );
+    }
+    for (lines_index, completion) in optional_completions.unwrap_or_default() {
+        eprintln!("Line {} is completed by \"{}\".", lines_index, completion);
+    }
 
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -52,24 +114,31 @@ pub enum Day10Error {
     CalculateTotalSyntaxErrorScore(#[from] CalculateTotalSyntaxErrorScoreError),
     #[error("Could not calculate total autocomplete score ({0})")]
     CalculateTotalAutocompleteScore(#[from] CalculateMiddleAutocompleteScoreError),
+    #[error("Could not create corruption report ({0})")]
+    CorruptionReport(#[from] CorruptionReportError),
+    #[error("Could not determine completions ({0})")]
+    Completions(#[from] CompletionsError),
 }
 
 pub fn calculate_total_syntax_error_score(
     navigation_subsystem: &str,
 ) -> Result<u128, CalculateTotalSyntaxErrorScoreError> {
-    Ok(parse_navigation_subsystem_lines(navigation_subsystem)
-        .into_iter()
-        .filter_map(Result::err)
+    total_syntax_error_score_of(&parse_navigation_subsystem_lines(navigation_subsystem))
+}
+
+fn total_syntax_error_score_of(
+    parsed_lines: &[ParsedLineResult],
+) -> Result<u128, CalculateTotalSyntaxErrorScoreError> {
+    Ok(parsed_lines
+        .iter()
+        .filter_map(|result| result.as_ref().err())
         .map(|error| match error {
             ParseNavigationSubsystemLineError::ExpectedButFound(_, found_symbol, _, _) => {
-                Ok(match found_symbol {
-                    SyntaxSymbol::Parentheses(_) => 3,
-                    SyntaxSymbol::Brackets(_) => 57,
-                    SyntaxSymbol::Braces(_) => 1197,
-                    SyntaxSymbol::AngleBrackets(_) => 25137,
-                })
+                Ok(syntax_error_score(found_symbol))
             }
-            _ => Err(CalculateTotalSyntaxErrorScoreError::ParseNavigationSubsystemLine(error)),
+            _ => Err(
+                CalculateTotalSyntaxErrorScoreError::ParseNavigationSubsystemLine(error.clone()),
+            ),
         })
         .collect::<Result<Vec<u128>, CalculateTotalSyntaxErrorScoreError>>()?
         .into_iter()
@@ -82,24 +151,113 @@ pub enum CalculateTotalSyntaxErrorScoreError {
     ParseNavigationSubsystemLine(#[source] ParseNavigationSubsystemLineError),
 }
 
+pub fn corruption_report(
+    navigation_subsystem: &str,
+) -> Result<Vec<(usize, char, u128)>, CorruptionReportError> {
+    corruption_report_of(&parse_navigation_subsystem_lines(navigation_subsystem))
+}
+
+fn corruption_report_of(
+    parsed_lines: &[ParsedLineResult],
+) -> Result<Vec<(usize, char, u128)>, CorruptionReportError> {
+    parsed_lines
+        .iter()
+        .filter_map(|result| result.as_ref().err())
+        .map(|error| match error {
+            ParseNavigationSubsystemLineError::ExpectedButFound(
+                _,
+                found_symbol,
+                lines_index,
+                _,
+            ) => Ok((
+                *lines_index,
+                found_symbol.to_char(),
+                syntax_error_score(found_symbol),
+            )),
+            _ => Err(CorruptionReportError::ParseNavigationSubsystemLine(
+                error.clone(),
+            )),
+        })
+        .collect()
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum CorruptionReportError {
+    #[error("Could not parse navigation subsystem line ({0})")]
+    ParseNavigationSubsystemLine(#[source] ParseNavigationSubsystemLineError),
+}
+
+pub fn completions(navigation_subsystem: &str) -> Result<Vec<(usize, String)>, CompletionsError> {
+    completions_of(&parse_navigation_subsystem_lines(navigation_subsystem))
+}
+
+fn completions_of(
+    parsed_lines: &[ParsedLineResult],
+) -> Result<Vec<(usize, String)>, CompletionsError> {
+    Ok(parsed_lines
+        .iter()
+        .enumerate()
+        .map(|(lines_index, result)| match result {
+            Ok(parsed_line) => Ok(Some((lines_index, parsed_line))),
+            Err(ParseNavigationSubsystemLineError::ExpectedButFound(_, _, _, _)) => Ok(None),
+            Err(error) => Err(error.clone()),
+        })
+        .collect::<Result<Vec<Option<(usize, &ParsedLine)>>, ParseNavigationSubsystemLineError>>()?
+        .into_iter()
+        .flatten()
+        .filter(|(_, parsed_line)| !parsed_line.missing_closing_symbols_stack.is_empty())
+        .map(|(lines_index, parsed_line)| {
+            (
+                lines_index,
+                parsed_line
+                    .missing_closing_symbols_stack
+                    .iter()
+                    .rev()
+                    .copied()
+                    .map(SyntaxSymbol::to_char)
+                    .collect(),
+            )
+        })
+        .collect())
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum CompletionsError {
+    #[error("Could not parse navigation subsystem line ({0})")]
+    ParseNavigationSubsystemLine(#[from] ParseNavigationSubsystemLineError),
+}
+
+fn syntax_error_score(found_symbol: &SyntaxSymbol) -> u128 {
+    match found_symbol {
+        SyntaxSymbol::Parentheses(_) => 3,
+        SyntaxSymbol::Brackets(_) => 57,
+        SyntaxSymbol::Braces(_) => 1197,
+        SyntaxSymbol::AngleBrackets(_) => 25137,
+    }
+}
+
 pub fn calculate_middle_autocomplete_score(
     navigation_subsystem: &str,
 ) -> Result<u128, CalculateMiddleAutocompleteScoreError> {
-    let mut autocompletion_score_per_line = parse_navigation_subsystem_lines(navigation_subsystem)
-        .into_iter()
+    middle_autocomplete_score_of(&parse_navigation_subsystem_lines(navigation_subsystem))
+}
+
+fn middle_autocomplete_score_of(
+    parsed_lines: &[ParsedLineResult],
+) -> Result<u128, CalculateMiddleAutocompleteScoreError> {
+    let mut autocompletion_score_per_line = parsed_lines
+        .iter()
         .map(|result| match result {
             Ok(_) | Err(ParseNavigationSubsystemLineError::ExpectedButFound(_, _, _, _)) => {
                 Ok(result)
             }
-            Err(error) => Err(error),
+            Err(error) => Err(error.clone()),
         })
-        .collect::<Result<
-            Vec<Result<ParsedLine, ParseNavigationSubsystemLineError>>,
-            ParseNavigationSubsystemLineError,
-        >>()?
+        .collect::<Result<Vec<&ParsedLineResult>, ParseNavigationSubsystemLineError>>()?
         .into_iter()
-        .filter_map(Result::ok)
-        .map(|parsed_line: ParsedLine| {
+        .filter_map(|result| result.as_ref().ok())
+        .filter(|parsed_line| !parsed_line.missing_closing_symbols_stack.is_empty())
+        .map(|parsed_line| {
             let mut score = 0;
             for current_symbol in parsed_line.missing_closing_symbols_stack.iter().rev() {
                 score *= 5;
@@ -114,24 +272,31 @@ pub fn calculate_middle_autocomplete_score(
         })
         .collect::<Vec<u128>>();
     autocompletion_score_per_line.sort_unstable();
-    Ok(if autocompletion_score_per_line.len() % 2 == 0 {
-        (autocompletion_score_per_line[autocompletion_score_per_line.len() / 2]
-            + autocompletion_score_per_line[autocompletion_score_per_line.len() / 3])
-            / 2
+    let middle_index = autocompletion_score_per_line.len() / 2;
+    if autocompletion_score_per_line.is_empty() {
+        Err(CalculateMiddleAutocompleteScoreError::NoIncompleteLines)
+    } else if autocompletion_score_per_line.len() % 2 == 0 {
+        // with an even count the median is the (rounded down) mean of both middle scores,
+        // as the scores are sorted adding half of their difference cannot overflow
+        let lower_middle_score = autocompletion_score_per_line[middle_index - 1];
+        let upper_middle_score = autocompletion_score_per_line[middle_index];
+        Ok(lower_middle_score + (upper_middle_score - lower_middle_score) / 2)
     } else {
-        autocompletion_score_per_line[autocompletion_score_per_line.len() / 2]
-    })
+        Ok(autocompletion_score_per_line[middle_index])
+    }
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
 pub enum CalculateMiddleAutocompleteScoreError {
     #[error("Could not parse navigation subsystem line ({0})")]
     ParseNavigationSubsystemLine(#[from] ParseNavigationSubsystemLineError),
+    #[error("There are no incomplete lines")]
+    NoIncompleteLines,
 }
 
-fn parse_navigation_subsystem_lines(
-    navigation_subsystem: &str,
-) -> Vec<Result<ParsedLine, ParseNavigationSubsystemLineError>> {
+type ParsedLineResult = Result<ParsedLine, ParseNavigationSubsystemLineError>;
+
+fn parse_navigation_subsystem_lines(navigation_subsystem: &str) -> Vec<ParsedLineResult> {
     navigation_subsystem
         .lines()
         .enumerate()
@@ -184,7 +349,7 @@ impl ParsedLine {
     }
 }
 
-#[derive(Debug, Error, Eq, PartialEq)]
+#[derive(Debug, Clone, Error, Eq, PartialEq)]
 pub enum ParseNavigationSubsystemLineError {
     #[error("Line no. {0} is empty")]
     LineIsEmpty(usize),
@@ -222,6 +387,19 @@ impl SyntaxSymbol {
             SyntaxSymbol::AngleBrackets(_) => SyntaxSymbol::AngleBrackets(SymbolVariation::Closing),
         }
     }
+
+    fn to_char(self) -> char {
+        match self {
+            SyntaxSymbol::Parentheses(SymbolVariation::Opening) => '(',
+            SyntaxSymbol::Parentheses(SymbolVariation::Closing) => ')',
+            SyntaxSymbol::Brackets(SymbolVariation::Opening) => '[',
+            SyntaxSymbol::Brackets(SymbolVariation::Closing) => ']',
+            SyntaxSymbol::Braces(SymbolVariation::Opening) => '{',
+            SyntaxSymbol::Braces(SymbolVariation::Closing) => '}',
+            SyntaxSymbol::AngleBrackets(SymbolVariation::Opening) => '<',
+            SyntaxSymbol::AngleBrackets(SymbolVariation::Closing) => '>',
+        }
+    }
 }
 
 impl TryFrom<char> for SyntaxSymbol {
@@ -242,7 +420,7 @@ impl TryFrom<char> for SyntaxSymbol {
     }
 }
 
-#[derive(Debug, Error, Eq, PartialEq)]
+#[derive(Debug, Clone, Error, Eq, PartialEq)]
 #[error("Expected some of '(', ')', '[', ']', '{{', '}}', '<' or '>', but got {0}.")]
 pub struct SyntaxSymbolTryFromError(char);
 
@@ -259,11 +437,7 @@ mod tests {
     #[test]
     fn calculate_total_syntax_error_score_should_return_26397() {
         // given
-        let input = "[({(<(())[]>[[{[]{<()<>>\r\n[(()[<>])]({[<{<<[]>>(\r\n\
-                            {([(<{}[<>[]}>{[]{[(<()>\r\n(((({<>}<{<{<>}{[]{[]{}\r\n\
-                            [[<[([]))<([[{}[[()]]]\r\n[{[{({}]{}}([{[{{{}}([]\r\n\
-                            {<[[]]>}<{[{[{[]{()[[[]\r\n[<(<(<(<{}))><([]([]()\r\n\
-                            <{([([[(<>()){}]>(<<{{\r\n<{([{{}}[<[[[<>{}]]]>[]]";
+        let input = EXAMPLE;
 
         // when
         let total_syntax_error_score = calculate_total_syntax_error_score(input);
@@ -275,11 +449,7 @@ mod tests {
     #[test]
     fn calculate_total_syntax_error_score_should_return_288957() {
         // given
-        let input = "[({(<(())[]>[[{[]{<()<>>\r\n[(()[<>])]({[<{<<[]>>(\r\n\
-                            {([(<{}[<>[]}>{[]{[(<()>\r\n(((({<>}<{<{<>}{[]{[]{}\r\n\
-                            [[<[([]))<([[{}[[()]]]\r\n[{[{({}]{}}([{[{{{}}([]\r\n\
-                            {<[[]]>}<{[{[{[]{()[[[]\r\n[<(<(<(<{}))><([]([]()\r\n\
-                            <{([([[(<>()){}]>(<<{{\r\n<{([{{}}[<[[[<>{}]]]>[]]";
+        let input = EXAMPLE;
 
         // when
         let middle_autocomplete_score = calculate_middle_autocomplete_score(input);
@@ -287,4 +457,96 @@ mod tests {
         // then
         assert_eq!(middle_autocomplete_score, Ok(288957));
     }
+
+    #[test]
+    fn calculate_middle_autocomplete_score_with_even_count_should_return_mean_of_middle_scores() {
+        // given
+        let input = "(\r\n[\r\n{\r\n<";
+
+        // when
+        let middle_autocomplete_score = calculate_middle_autocomplete_score(input);
+
+        // then
+        assert_eq!(middle_autocomplete_score, Ok(2));
+    }
+
+    #[test]
+    fn calculate_middle_autocomplete_score_without_incomplete_lines_should_fail() {
+        // given
+        let input = "()\r\n[]";
+
+        // when
+        let middle_autocomplete_score = calculate_middle_autocomplete_score(input);
+
+        // then
+        assert_eq!(
+            middle_autocomplete_score,
+            Err(CalculateMiddleAutocompleteScoreError::NoIncompleteLines)
+        );
+    }
+
+    #[test]
+    fn corruption_report_should_return_five_corrupted_lines() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let report = corruption_report(input);
+
+        // then
+        assert_eq!(
+            report,
+            Ok(vec![
+                (2, '}', 1197),
+                (4, ')', 3),
+                (5, ']', 57),
+                (7, ')', 3),
+                (8, '>', 25137),
+            ])
+        );
+    }
+
+    #[test]
+    fn completions_should_complete_first_line() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let completions = completions(input);
+
+        // then
+        assert_eq!(
+            completions.map(|completions| completions.first().cloned()),
+            Ok(Some((0, String::from("}}]])})]"))))
+        );
+    }
+
+    #[test]
+    fn syntax_symbol_to_char_should_round_trip() {
+        for character in ['(', ')', '[', ']', '{', '}', '<', '>'] {
+            // given
+            let syntax_symbol = SyntaxSymbol::try_from(character);
+
+            // when
+            let rendered = syntax_symbol.map(|syntax_symbol| syntax_symbol.to_char());
+
+            // then
+            assert_eq!(rendered, Ok(character));
+        }
+    }
+
+    #[test]
+    fn calculate_middle_autocomplete_score_with_huge_even_count_scores_should_not_overflow() {
+        // given
+        let input = format!("{}\r\n{}", "<".repeat(55), "<".repeat(55));
+
+        // when
+        let middle_autocomplete_score = calculate_middle_autocomplete_score(&input);
+
+        // then
+        assert_eq!(
+            middle_autocomplete_score,
+            Ok((0..55).fold(0u128, |score, _| score * 5 + 4))
+        );
+    }
 }
@@ -1,49 +1,95 @@
+use std::num::ParseIntError;
 use std::str::FromStr;
+use std::time::Instant;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_puzzle_part_time_two,
+    clap_arg_repeat, clap_arg_time, neighbors8, parse_digit_grid, part_from_matches, print_answer,
+    print_elapsed_time, read_input_contents, solve_repeatedly, GridParseError, Part,
+    ReadFileContentsError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day11";
+pub const ABOUT: &str = "My solution for Day 11: Dumbo Octopus";
+
+pub const EXAMPLE: &str = "5483143223\r\n2745854711\r\n5264556173\r\n6141336146\r\n6357385478\r\n\
+    4167524645\r\n2176841721\r\n6882881134\r\n4846848554\r\n5283751526";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 11: Dumbo Octopus")
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day11-input"))
+        .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
         .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day11-input"),
+            Arg::with_name("steps")
+                .short("s")
+                .long("steps")
+                .value_name("STEPS")
+                .help("sets the count of steps to simulate for part one (defaults to 100)"),
         )
-        .arg(clap_arg_puzzle_part_time_two())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day11Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day11Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => {
+    let solving_start = Instant::now();
+    match part_from_matches(matches) {
+        Part::Two => {
             let first_step_during_which_all_flash =
-                calculate_first_step_during_which_all_flash(&file_contents)?;
-            println!(
-                "The first step during which all octopuses will all flash is step number {}.",
-                first_step_during_which_all_flash
+                solve_repeatedly(matches, || find_first_synchronized_flash(&file_contents))?;
+            print_answer(
+                matches,
+                11,
+                2,
+                first_step_during_which_all_flash,
+                format!(
+                    "The first step during which all octopuses will all flash is step number {}.",
+                    first_step_during_which_all_flash
+                ),
             );
         }
-        _ => {
-            let total_flashes_after_100_steps =
-                calculate_total_flashes_after_100_steps(&file_contents)?;
-            println!(
-                "There were {} total flashes after 100 steps.",
-                total_flashes_after_100_steps
+        Part::One => {
+            let (steps, total_flashes) = match matches.value_of("steps") {
+                Some(steps) => {
+                    let steps = steps
+                        .parse::<u128>()
+                        .map_err(|error| Day11Error::ParseSteps(steps.to_string(), error))?;
+                    (
+                        steps,
+                        solve_repeatedly(matches, || {
+                            calculate_total_flashes(&file_contents, steps)
+                        })?,
+                    )
+                }
+                None => (
+                    100,
+                    solve_repeatedly(matches, || {
+                        calculate_total_flashes_after_100_steps(&file_contents)
+                    })?,
+                ),
+            };
+            print_answer(
+                matches,
+                11,
+                1,
+                total_flashes,
+                format!(
+                    "There were {} total flashes after {} steps.",
+                    total_flashes, steps
+                ),
             );
         }
     };
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -51,17 +97,34 @@ pub fn handle(matches: &ArgMatches) -> Result<(), Day11Error> {
 pub enum Day11Error {
     #[error("Could not read file contents of \"{0:?}\" ({1})")]
     ReadFileContents(Option<String>, #[source] ReadFileContentsError),
+    #[error("Could not parse steps \"{0}\" ({1})")]
+    ParseSteps(String, #[source] ParseIntError),
+    #[error("Could not calculate total flashes ({0})")]
+    CalculateTotalFlashes(#[from] CalculateTotalFlashesError),
     #[error("Could not calculate total flashes after 100 steps ({0})")]
     CalculateTotalFlashesAfter100Steps(#[from] CalculateTotalFlashesAfter100StepsError),
-    #[error("Could not calculate first step during which all flash ({0})")]
-    CalculateFirstStepDuringWhichAllFlash(#[from] CalculateFirstStepDuringWhichAllFlashError),
+    #[error("Could not find first synchronized flash ({0})")]
+    FindFirstSynchronizedFlash(#[from] FindFirstSynchronizedFlashError),
 }
 
 pub fn calculate_total_flashes_after_100_steps(
     octopus_grid: &str,
 ) -> Result<u128, CalculateTotalFlashesAfter100StepsError> {
+    Ok(calculate_total_flashes(octopus_grid, 100)?)
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum CalculateTotalFlashesAfter100StepsError {
+    #[error(transparent)]
+    CalculateTotalFlashes(#[from] CalculateTotalFlashesError),
+}
+
+pub fn calculate_total_flashes(
+    octopus_grid: &str,
+    steps: u128,
+) -> Result<u128, CalculateTotalFlashesError> {
     let octopus_grid = OctopusGrid::from_str(octopus_grid)?;
-    Ok((0..100)
+    Ok((0..steps)
         .fold((octopus_grid, 0), |(octopus_grid, flash_count), _| {
             let (new_octopus_grid, additional_flashes) = simulate_step(octopus_grid);
             (new_octopus_grid, flash_count + additional_flashes)
@@ -70,14 +133,14 @@ pub fn calculate_total_flashes_after_100_steps(
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
-pub enum CalculateTotalFlashesAfter100StepsError {
+pub enum CalculateTotalFlashesError {
     #[error("Could not parse octopus grid ({0})")]
     OctopusGridFromStr(#[from] OctopusGridFromStrError),
 }
 
-pub fn calculate_first_step_during_which_all_flash(
+pub fn find_first_synchronized_flash(
     octopus_grid: &str,
-) -> Result<u128, CalculateFirstStepDuringWhichAllFlashError> {
+) -> Result<u128, FindFirstSynchronizedFlashError> {
     let mut octopus_grid = OctopusGrid::from_str(octopus_grid)?;
     let mut step = 0;
     Ok(loop {
@@ -91,7 +154,7 @@ pub fn calculate_first_step_during_which_all_flash(
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
-pub enum CalculateFirstStepDuringWhichAllFlashError {
+pub enum FindFirstSynchronizedFlashError {
     #[error("Could not parse octopus grid ({0})")]
     OctopusGridFromStr(#[from] OctopusGridFromStrError),
 }
@@ -104,33 +167,19 @@ impl FromStr for OctopusGrid {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Self(
-            s.lines()
+            parse_digit_grid(s)?
+                .into_iter()
                 .enumerate()
                 .map(|(lines_index, line)| {
-                    line.chars()
-                        .map(|character| match character {
-                            '0' => Ok(Octopus { energy_level: 0 }),
-                            '1' => Ok(Octopus { energy_level: 1 }),
-                            '2' => Ok(Octopus { energy_level: 2 }),
-                            '3' => Ok(Octopus { energy_level: 3 }),
-                            '4' => Ok(Octopus { energy_level: 4 }),
-                            '5' => Ok(Octopus { energy_level: 5 }),
-                            '6' => Ok(Octopus { energy_level: 6 }),
-                            '7' => Ok(Octopus { energy_level: 7 }),
-                            '8' => Ok(Octopus { energy_level: 8 }),
-                            '9' => Ok(Octopus { energy_level: 9 }),
-                            _ => Err(OctopusGridFromStrError::InvalidCharacter(character)),
-                        })
-                        .collect::<Result<Vec<Octopus>, OctopusGridFromStrError>>()
-                        .and_then(|line_elements| {
-                            line_elements
-                                .try_into()
-                                .map_err(|line_elements_err: Vec<Octopus>| {
-                                    OctopusGridFromStrError::InvalidLineLength(
-                                        lines_index,
-                                        line_elements_err.len(),
-                                    )
-                                })
+                    line.into_iter()
+                        .map(|energy_level| Octopus { energy_level })
+                        .collect::<Vec<Octopus>>()
+                        .try_into()
+                        .map_err(|line_elements_err: Vec<Octopus>| {
+                            OctopusGridFromStrError::InvalidLineLength(
+                                lines_index,
+                                line_elements_err.len(),
+                            )
                         })
                 })
                 .collect::<Result<Vec<[Octopus; 10]>, OctopusGridFromStrError>>()?
@@ -146,8 +195,8 @@ impl FromStr for OctopusGrid {
 // allowing clippy::enum_variant_names because i don't know how to name them otherwise
 #[allow(clippy::enum_variant_names)]
 pub enum OctopusGridFromStrError {
-    #[error("Expected to find number, but found '{0}'")]
-    InvalidCharacter(char),
+    #[error("Could not parse digit grid ({0})")]
+    ParseDigitGrid(#[from] GridParseError),
     #[error("Line no. {0} has {1} elements, but 10 were expected")]
     InvalidLineLength(usize, usize),
     #[error("Found {0} lines, but 10 were expected")]
@@ -161,8 +210,7 @@ struct Octopus {
 
 fn simulate_step(mut octopus_grid: OctopusGrid) -> (OctopusGrid, u128) {
     let mut flash_queue = (0..10)
-        .into_iter()
-        .flat_map(|y| (0..10).into_iter().map(move |x| (x, y)))
+        .flat_map(|y| (0..10).map(move |x| (x, y)))
         .filter_map(|(x, y)| {
             octopus_grid.0[y][x].energy_level += 1;
             if octopus_grid.0[y][x].energy_level > 9 {
@@ -174,33 +222,15 @@ fn simulate_step(mut octopus_grid: OctopusGrid) -> (OctopusGrid, u128) {
         .collect::<Vec<(usize, usize)>>();
     while !flash_queue.is_empty() {
         let (x, y) = flash_queue.remove(0);
-        let (x, y) = (x as isize, y as isize);
-
-        let mut increase_neighbour = |x: isize, y: isize| {
-            if y >= 0
-                && y < octopus_grid.0.len() as isize
-                && x >= 0
-                && x < octopus_grid.0[y as usize].len() as isize
-            {
-                let (x, y) = (x as usize, y as usize);
-                octopus_grid.0[y][x].energy_level += 1;
-                if octopus_grid.0[y][x].energy_level == 10 {
-                    flash_queue.push((x, y));
-                }
-            }
-        };
-
-        for iy in (y - 1)..=(y + 1) {
-            for ix in (x - 1)..=(x + 1) {
-                if !(iy == y && ix == x) {
-                    increase_neighbour(ix, iy);
-                }
+        for (x, y) in neighbors8(x, y, 10, 10) {
+            octopus_grid.0[y][x].energy_level += 1;
+            if octopus_grid.0[y][x].energy_level == 10 {
+                flash_queue.push((x, y));
             }
         }
     }
     let flash_counter = (0..10)
-        .into_iter()
-        .flat_map(|y| (0..10).into_iter().map(move |x| (x, y)))
+        .flat_map(|y| (0..10).map(move |x| (x, y)))
         .map(|(x, y)| {
             if octopus_grid.0[y][x].energy_level > 9 {
                 octopus_grid.0[y][x].energy_level = 0;
@@ -220,8 +250,7 @@ mod tests {
     #[test]
     fn calculate_total_flashes_after_100_steps_should_return_1656() {
         // given
-        let input = "5483143223\r\n2745854711\r\n5264556173\r\n6141336146\r\n6357385478\r\n\
-                            4167524645\r\n2176841721\r\n6882881134\r\n4846848554\r\n5283751526";
+        let input = EXAMPLE;
 
         // when
         let total_flashes_after_100_steps = calculate_total_flashes_after_100_steps(input);
@@ -230,6 +259,18 @@ mod tests {
         assert_eq!(total_flashes_after_100_steps, Ok(1656));
     }
 
+    #[test]
+    fn calculate_total_flashes_after_10_steps_should_return_204() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let total_flashes = calculate_total_flashes(input, 10);
+
+        // then
+        assert_eq!(total_flashes, Ok(204));
+    }
+
     #[test]
     fn test_simulate_step() {
         // given
@@ -273,13 +314,12 @@ mod tests {
     }
 
     #[test]
-    fn calculate_first_step_during_which_all_flash_should_return_195() {
+    fn find_first_synchronized_flash_should_return_195() {
         // given
-        let input = "5483143223\r\n2745854711\r\n5264556173\r\n6141336146\r\n6357385478\r\n\
-                            4167524645\r\n2176841721\r\n6882881134\r\n4846848554\r\n5283751526";
+        let input = EXAMPLE;
 
         // when
-        let first_step_during_which_all_flash = calculate_first_step_during_which_all_flash(input);
+        let first_step_during_which_all_flash = find_first_synchronized_flash(input);
 
         // then
         assert_eq!(first_step_during_which_all_flash, Ok(195));
@@ -1,52 +1,93 @@
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
+use std::time::Instant;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_puzzle_part_time_two,
+    clap_arg_repeat, clap_arg_time, part_from_matches, print_answer, print_elapsed_time,
+    read_input_contents, solve_repeatedly, Part, ReadFileContentsError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day12";
+pub const ABOUT: &str = "My solution for Day 12: Passage Pathing";
+
+pub const EXAMPLE: &str = "start-A\r\nstart-b\r\nA-c\r\nA-b\r\nb-d\r\nA-end\r\nb-end";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 12: Passage Pathing")
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day12-input"))
+        .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
         .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day12-input"),
+            Arg::with_name("count_only")
+                .long("count-only")
+                .help("only counts the paths without storing them (default)")
+                .conflicts_with("list_paths"),
+        )
+        .arg(
+            Arg::with_name("list_paths")
+                .long("list-paths")
+                .help("prints every path through the cave system to stderr"),
         )
-        .arg(clap_arg_puzzle_part_time_two())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day12Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day12Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => {
-            let paths_count = count_paths_in_specific_way(
-                &file_contents,
-                CaveVisitVariation::OneSmallOneTwiceRemainingOnce,
-            )?;
-            println!(
-                "There are {} paths through this cave system that visit small caves at once, but one small one twice.",
-                paths_count
+    let solving_start = Instant::now();
+    let cave_visit_variation = match part_from_matches(matches) {
+        Part::Two => CaveVisitVariation::OneSmallOneTwiceRemainingOnce,
+        Part::One => CaveVisitVariation::SmallOnesOnce,
+    };
+    let list_paths = matches.is_present("list_paths");
+    let (paths_count, optional_paths) = solve_repeatedly(matches, || {
+        if list_paths {
+            let paths = paths(&file_contents, cave_visit_variation)?;
+            Ok::<_, Day12Error>((paths.len() as u128, Some(paths)))
+        } else {
+            Ok((
+                count_paths_in_specific_way(&file_contents, cave_visit_variation)?,
+                None,
+            ))
+        }
+    })?;
+    for path in optional_paths.unwrap_or_default() {
+        eprintln!("{}", path);
+    }
+    match part_from_matches(matches) {
+        Part::Two => {
+            print_answer(
+                matches,
+                12,
+                2,
+                paths_count,
+                format!("There are {} paths through this cave system that visit small caves at once, but one small one twice.", paths_count),
             );
         }
-        _ => {
-            let paths_count =
-                count_paths_in_specific_way(&file_contents, CaveVisitVariation::SmallOnesOnce)?;
-            println!(
-                "There are {} paths through this cave system that visit small caves at once.",
-                paths_count
+        Part::One => {
+            print_answer(
+                matches,
+                12,
+                1,
+                paths_count,
+                format!(
+                    "There are {} paths through this cave system that visit small caves at once.",
+                    paths_count
+                ),
             );
         }
     };
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -56,30 +97,95 @@ pub enum Day12Error {
     ReadFileContents(Option<String>, #[source] ReadFileContentsError),
     #[error("Could not count paths in specific way ({0})")]
     CountPathsInSpecificWay(#[from] CountPathsInSpecificWayError),
+    #[error("Could not find paths ({0})")]
+    Paths(#[from] PathsError),
 }
 
 pub fn count_paths_in_specific_way(
     rough_map: &str,
     cave_visit_variation: CaveVisitVariation,
 ) -> Result<u128, CountPathsInSpecificWayError> {
+    Ok(count_paths(
+        &RoughMap::from_str(rough_map)?,
+        cave_visit_variation,
+    )?)
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum CountPathsInSpecificWayError {
+    #[error("Could not parse rough map ({0})")]
+    RoughMapFromStr(#[from] RoughMapFromStrError),
+    #[error("Could not find paths in specific way ({0})")]
+    FindPathsInSpecificWay(#[from] FindPathsInSpecificWayError),
+}
+
+pub fn paths(
+    rough_map: &str,
+    cave_visit_variation: CaveVisitVariation,
+) -> Result<Vec<String>, PathsError> {
     Ok(
-        find_paths_in_specific_way(&RoughMap::from_str(rough_map)?, cave_visit_variation)?.len()
-            as u128,
+        find_paths_in_specific_way(&RoughMap::from_str(rough_map)?, cave_visit_variation)?
+            .iter()
+            .map(MapPath::to_string)
+            .collect(),
     )
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
-pub enum CountPathsInSpecificWayError {
+pub enum PathsError {
     #[error("Could not parse rough map ({0})")]
     RoughMapFromStr(#[from] RoughMapFromStrError),
     #[error("Could not find paths in specific way ({0})")]
     FindPathsInSpecificWay(#[from] FindPathsInSpecificWayError),
 }
 
+fn count_paths(
+    rough_map: &RoughMap,
+    cave_visit_variation: CaveVisitVariation,
+) -> Result<u128, FindPathsInSpecificWayError> {
+    let (start, end) = find_start_and_end(rough_map)?;
+    let mut paths_count = 0;
+    calculate_edges_path(
+        start,
+        start,
+        end,
+        &mut Vec::new(),
+        &mut vec![0; rough_map.vertices.len()],
+        false,
+        rough_map,
+        cave_visit_variation,
+        &mut |_| paths_count += 1,
+    );
+    Ok(paths_count)
+}
+
 fn find_paths_in_specific_way(
     rough_map: &RoughMap,
     cave_visit_variation: CaveVisitVariation,
 ) -> Result<Vec<MapPath>, FindPathsInSpecificWayError> {
+    let (start, end) = find_start_and_end(rough_map)?;
+    let mut paths = Vec::new();
+    calculate_edges_path(
+        start,
+        start,
+        end,
+        &mut Vec::new(),
+        &mut vec![0; rough_map.vertices.len()],
+        false,
+        rough_map,
+        cave_visit_variation,
+        &mut |path| {
+            paths.push(MapPath(
+                path.iter()
+                    .map(|vertex| rough_map.vertices[*vertex].clone())
+                    .collect(),
+            ))
+        },
+    );
+    Ok(paths)
+}
+
+fn find_start_and_end(rough_map: &RoughMap) -> Result<(usize, usize), FindPathsInSpecificWayError> {
     if !rough_map.vertices.contains(&"start".to_string()) {
         return Err(FindPathsInSpecificWayError::MissingStartVertex);
     }
@@ -97,95 +203,57 @@ fn find_paths_in_specific_way(
         return Err(FindPathsInSpecificWayError::NoEdgeToEnd);
     }
 
-    fn calculate_edges_path(
-        current_edge: &(String, String),
-        never_target_vertex: &str,
-        target_vertex: &str,
-        initial_path: &[(String, String)],
-        visit_counters: &HashMap<String, u128>,
-        rough_map: &RoughMap,
-        cave_visit_variation: CaveVisitVariation,
-    ) -> Vec<Vec<(String, String)>> {
-        let current_target_is_small_cave = current_edge.1.is_lowercase();
-        let current_target_visit_counter =
-            visit_counters.get(&current_edge.1).copied().unwrap_or(0);
-        let current_target_will_be_second_visited = current_target_visit_counter == 1;
-
-        let invalid_visit = match cave_visit_variation {
-            CaveVisitVariation::SmallOnesOnce => {
-                current_target_is_small_cave && current_target_will_be_second_visited
-            }
-            CaveVisitVariation::OneSmallOneTwiceRemainingOnce => {
-                let any_small_cave_visited_min_twice = visit_counters
-                    .iter()
-                    .any(|(cave, counter)| cave.is_lowercase() && *counter >= 2);
-                let current_target_will_be_second_visited_min_twice =
-                    any_small_cave_visited_min_twice && current_target_visit_counter != 0;
-                let current_target_should_never_be_targeted = current_edge.1 == never_target_vertex;
-                let never_to_target_gets_targeted =
-                    current_target_should_never_be_targeted && current_target_visit_counter == 1;
-                current_target_is_small_cave
-                    && (current_target_will_be_second_visited_min_twice
-                        || never_to_target_gets_targeted)
-            }
-        };
+    Ok((
+        rough_map.vertex_id("start").unwrap(),
+        rough_map.vertex_id("end").unwrap(),
+    ))
+}
 
-        if invalid_visit {
-            Vec::new()
-        } else {
-            let mut new_path = initial_path.to_owned();
-            let mut new_visit_counters = visit_counters.clone();
-            if new_path.is_empty() {
-                new_visit_counters
-                    .entry(current_edge.0.clone())
-                    .and_modify(|c| *c += 1)
-                    .or_insert(1);
-            }
-            new_visit_counters
-                .entry(current_edge.1.clone())
-                .and_modify(|c| *c += 1)
-                .or_insert(1);
-            new_path.push(current_edge.clone());
-            if current_edge.1 == target_vertex {
-                vec![new_path]
-            } else {
-                rough_map
-                    .edges
-                    .iter()
-                    .filter(|(s, _)| *s == current_edge.1)
-                    .flat_map(|next_edge| {
-                        calculate_edges_path(
-                            next_edge,
-                            never_target_vertex,
-                            target_vertex,
-                            &new_path.clone(),
-                            &new_visit_counters.clone(),
-                            rough_map,
-                            cave_visit_variation,
-                        )
-                    })
-                    .collect()
+#[allow(clippy::too_many_arguments)]
+fn calculate_edges_path<F: FnMut(&[usize])>(
+    current_vertex: usize,
+    never_target_vertex: usize,
+    target_vertex: usize,
+    path: &mut Vec<usize>,
+    visit_counters: &mut Vec<u128>,
+    any_small_cave_visited_twice: bool,
+    rough_map: &RoughMap,
+    cave_visit_variation: CaveVisitVariation,
+    on_path_found: &mut F,
+) {
+    path.push(current_vertex);
+    visit_counters[current_vertex] += 1;
+    if current_vertex == target_vertex {
+        on_path_found(path);
+    } else {
+        for next_vertex in &rough_map.neighbor_ids[current_vertex] {
+            let next_is_small_cave = rough_map.small_caves[*next_vertex];
+            let next_already_visited = visit_counters[*next_vertex] != 0;
+            let invalid_visit = next_is_small_cave
+                && next_already_visited
+                && match cave_visit_variation {
+                    CaveVisitVariation::SmallOnesOnce => true,
+                    CaveVisitVariation::OneSmallOneTwiceRemainingOnce => {
+                        any_small_cave_visited_twice || *next_vertex == never_target_vertex
+                    }
+                };
+            if !invalid_visit {
+                calculate_edges_path(
+                    *next_vertex,
+                    never_target_vertex,
+                    target_vertex,
+                    path,
+                    visit_counters,
+                    any_small_cave_visited_twice || (next_is_small_cave && next_already_visited),
+                    rough_map,
+                    cave_visit_variation,
+                    on_path_found,
+                );
             }
         }
     }
-
-    Ok(rough_map
-        .edges
-        .iter()
-        .filter(|(s, _)| s.as_str() == "start")
-        .flat_map(|next_edge| {
-            calculate_edges_path(
-                next_edge,
-                "start",
-                "end",
-                &Vec::new(),
-                &HashMap::new(),
-                rough_map,
-                cave_visit_variation,
-            )
-        })
-        .map(MapPath::from)
-        .collect())
+    visit_counters[current_vertex] -= 1;
+    path.pop();
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
@@ -204,6 +272,15 @@ pub enum FindPathsInSpecificWayError {
 struct RoughMap {
     vertices: Vec<String>,
     edges: Vec<(String, String)>,
+    // "start" is never listed as a neighbor and "end" has no neighbors
+    neighbor_ids: Vec<Vec<usize>>,
+    small_caves: Vec<bool>,
+}
+
+impl RoughMap {
+    fn vertex_id(&self, vertex: &str) -> Option<usize> {
+        self.vertices.iter().position(|other| other == vertex)
+    }
 }
 
 impl FromStr for RoughMap {
@@ -245,7 +322,35 @@ impl FromStr for RoughMap {
                     (vertices, edges)
                 },
             );
-        Ok(Self { vertices, edges })
+        let neighbors = edges
+            .iter()
+            .filter(|(a, b)| a != "end" && b != "start")
+            .fold(HashMap::new(), |mut neighbors, (a, b)| {
+                neighbors
+                    .entry(a.clone())
+                    .or_insert_with(Vec::new)
+                    .push(b.clone());
+                neighbors
+            });
+        let vertex_id = |vertex: &String| vertices.iter().position(|other| other == vertex);
+        let neighbor_ids = vertices
+            .iter()
+            .map(|vertex| {
+                neighbors
+                    .get(vertex)
+                    .map(|vertex_neighbors: &Vec<String>| {
+                        vertex_neighbors.iter().filter_map(vertex_id).collect()
+                    })
+                    .unwrap_or_default()
+            })
+            .collect();
+        let small_caves = vertices.iter().map(IsLowercase::is_lowercase).collect();
+        Ok(Self {
+            vertices,
+            edges,
+            neighbor_ids,
+            small_caves,
+        })
     }
 }
 
@@ -280,6 +385,12 @@ impl From<Vec<(String, String)>> for MapPath {
     }
 }
 
+impl Display for MapPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.join(","))
+    }
+}
+
 trait IsLowercase {
     fn is_lowercase(&self) -> bool;
 }
@@ -303,7 +414,7 @@ mod tests {
     #[test]
     fn count_paths_in_specific_way_small_ones_once_should_return_10() {
         // given
-        let input = "start-A\r\nstart-b\r\nA-c\r\nA-b\r\nb-d\r\nA-end\r\nb-end";
+        let input = EXAMPLE;
 
         // when
         let count_of_paths = count_paths_in_specific_way(input, CaveVisitVariation::SmallOnesOnce);
@@ -315,7 +426,7 @@ mod tests {
     #[test]
     fn count_paths_in_specific_way_one_small_one_twice_should_return_36() {
         // given
-        let input = "start-A\r\nstart-b\r\nA-c\r\nA-b\r\nb-d\r\nA-end\r\nb-end";
+        let input = EXAMPLE;
 
         // when
         let count_of_paths =
@@ -381,10 +492,28 @@ mod tests {
         assert_eq!(count_of_paths, Ok(3509));
     }
 
+    #[test]
+    fn count_paths_should_agree_with_find_paths_in_specific_way() {
+        // given
+        let input = "fs-end\r\nhe-DX\r\nfs-he\r\nstart-DX\r\npj-DX\r\nend-zg\r\nzg-sl\r\n\
+                            zg-pj\r\npj-he\r\nRW-he\r\nfs-DX\r\npj-RW\r\nzg-RW\r\nstart-pj\r\n\
+                            he-WI\r\nzg-he\r\npj-fs\r\nstart-RW";
+        let rough_map = RoughMap::from_str(input).unwrap();
+
+        // when
+        let count_of_paths = count_paths(&rough_map, CaveVisitVariation::SmallOnesOnce);
+
+        // then
+        let map_paths =
+            find_paths_in_specific_way(&rough_map, CaveVisitVariation::SmallOnesOnce).unwrap();
+        assert_eq!(count_of_paths, Ok(226));
+        assert_eq!(map_paths.len(), 226);
+    }
+
     #[test]
     fn rough_map_from_str() {
         // given
-        let input = "start-A\r\nstart-b\r\nA-c\r\nA-b\r\nb-d\r\nA-end\r\nb-end";
+        let input = EXAMPLE;
 
         // when
         let rough_map = RoughMap::from_str(input);
@@ -416,7 +545,16 @@ mod tests {
                     ("end".to_string(), "A".to_string()),
                     ("b".to_string(), "end".to_string()),
                     ("end".to_string(), "b".to_string()),
-                ]
+                ],
+                neighbor_ids: vec![
+                    vec![1, 2],
+                    vec![3, 2, 5],
+                    vec![1, 4, 5],
+                    vec![1],
+                    vec![2],
+                    vec![],
+                ],
+                small_caves: vec![true, false, true, true, true, true],
             })
         );
     }
@@ -424,7 +562,7 @@ mod tests {
     #[test]
     fn test_find_paths_in_specific_way_small_ones_once() {
         // given
-        let input = "start-A\r\nstart-b\r\nA-c\r\nA-b\r\nb-d\r\nA-end\r\nb-end";
+        let input = EXAMPLE;
         let rough_map = RoughMap::from_str(input).unwrap();
 
         // when
@@ -504,4 +642,43 @@ mod tests {
             "end".to_string()
         ])));
     }
+
+    #[test]
+    fn paths_should_return_10_formatted_paths() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let paths = paths(input, CaveVisitVariation::SmallOnesOnce);
+
+        // then
+        let paths = paths.unwrap();
+        assert_eq!(paths.len(), 10);
+        for expected_path in [
+            "start,A,b,A,c,A,end",
+            "start,A,b,A,end",
+            "start,A,b,end",
+            "start,A,c,A,b,A,end",
+            "start,A,c,A,b,end",
+            "start,A,c,A,end",
+            "start,A,end",
+            "start,b,A,c,A,end",
+            "start,b,A,end",
+            "start,b,end",
+        ] {
+            assert!(paths.contains(&expected_path.to_string()));
+        }
+    }
+
+    #[test]
+    fn subcommand_should_reject_count_only_with_list_paths() {
+        // given
+        let arguments = vec!["day12", "--count-only", "--list-paths"];
+
+        // when
+        let matches = subcommand().get_matches_from_safe(arguments);
+
+        // then
+        assert!(matches.is_err());
+    }
 }
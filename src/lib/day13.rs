@@ -2,50 +2,104 @@ use std::cmp::Ordering;
 use std::fmt::Display;
 use std::num::ParseIntError;
 use std::str::FromStr;
+use std::time::Instant;
 
-use clap::{App, Arg, ArgMatches, SubCommand};
+use clap::{App, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_file, clap_arg_output_format,
+    clap_arg_puzzle_part_time_two, clap_arg_repeat, clap_arg_time, part_from_matches, print_answer,
+    print_elapsed_time, read_input_contents, solve_repeatedly, write_rendered, Part,
+    ReadFileContentsError, WriteRenderedError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day13";
+pub const ABOUT: &str = "My solution for Day 13: Transparent Origami";
+
+pub const EXAMPLE: &str = "6,10\r\n0,14\r\n9,10\r\n0,3\r\n10,4\r\n4,11\r\n6,0\r\n6,12\r\n4,1\r\n\
+    0,13\r\n10,12\r\n3,4\r\n3,0\r\n8,4\r\n1,10\r\n2,14\r\n8,10\r\n9,0\r\n\
+    \r\nfold along y=7\r\nfold along x=5";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 13: Transparent Origami")
-        .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day13-input"),
-        )
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day13-input"))
         .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
+        .arg(clap_arg_output_file())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day13Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day13Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => {
-            let folded_transparent_paper = fully_fold_transparent_paper(&file_contents)?;
-            println!(
-                "The fully folded transparent paper looks like:\r\n\r\n{}",
-                folded_transparent_paper
-            );
+    let solving_start = Instant::now();
+    match part_from_matches(matches) {
+        Part::Two => {
+            let folded_transparent_paper =
+                solve_repeatedly(matches, || fully_fold_transparent_paper(&file_contents))?;
+            // letters are only recognized on real puzzle inputs, the grid is still rendered otherwise
+            let optional_folded_code = read_letters(&folded_transparent_paper).ok();
+            let optional_rendered = match matches.value_of("output_file") {
+                // the paper itself has been printed to stdout, so there is nothing to announce
+                Some("-") => {
+                    write_rendered("-", &folded_transparent_paper)?;
+                    None
+                }
+                Some(output_file) => {
+                    write_rendered(output_file, &folded_transparent_paper)?;
+                    Some(format!(
+                        "The fully folded transparent paper has been written to \"{}\".",
+                        output_file
+                    ))
+                }
+                None => Some(format!(
+                    "The fully folded transparent paper looks like:\r\n\r\n{}",
+                    folded_transparent_paper
+                )),
+            };
+            match optional_folded_code {
+                Some(folded_code) => print_answer(
+                    matches,
+                    13,
+                    2,
+                    &folded_code,
+                    match optional_rendered {
+                        Some(rendered) => format!("{}\nWhich reads as: {}", rendered, folded_code),
+                        None => format!("Which reads as: {}", folded_code),
+                    },
+                ),
+                None => print_answer(
+                    matches,
+                    13,
+                    2,
+                    &folded_transparent_paper,
+                    optional_rendered.unwrap_or_default(),
+                ),
+            }
         }
-        _ => {
-            let count_of_dots_visible_after_folding =
-                count_dots_visible_after_folding_once(&file_contents)?;
-            println!(
-                "There are {} dots visible after completing just the first fold instruction.",
-                count_of_dots_visible_after_folding
+        Part::One => {
+            let count_of_dots_visible_after_folding = solve_repeatedly(matches, || {
+                count_dots_visible_after_folding_once(&file_contents)
+            })?;
+            print_answer(
+                matches,
+                13,
+                1,
+                count_of_dots_visible_after_folding,
+                format!(
+                    "There are {} dots visible after completing just the first fold instruction.",
+                    count_of_dots_visible_after_folding
+                ),
             );
         }
     };
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -57,34 +111,116 @@ pub enum Day13Error {
     CountDotsVisibleAfterFolding(#[from] CountDotsVisibleAfterFoldingError),
     #[error("Could not fully fold transparent paper ({0})")]
     FullyFoldTransparentPaper(#[from] FullyFoldTransparentPaperError),
+    #[error("Could not read folded code ({0})")]
+    ReadFoldedCode(#[from] ReadFoldedCodeError),
+    #[error("Could not write folded transparent paper ({0})")]
+    WriteRendered(#[from] WriteRenderedError),
 }
 
 pub fn count_dots_visible_after_folding_once(
     transparent_paper: &str,
 ) -> Result<u128, CountDotsVisibleAfterFoldingError> {
-    let mut transparent_paper = TransparentPaper::from_str(transparent_paper)?;
-    transparent_paper.fold();
-    Ok(transparent_paper.marked_dot_positions.len() as u128)
+    Ok(fold_n_times(transparent_paper, 1)?
+        .marked_dot_positions
+        .len() as u128)
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
 pub enum CountDotsVisibleAfterFoldingError {
-    #[error("Could not parse transparent paper ({0})")]
-    TransparentPaperFromStr(#[from] TransparentPaperFromStrError),
+    #[error(transparent)]
+    FoldNTimes(#[from] FoldNTimesError),
 }
 
 pub fn fully_fold_transparent_paper(
     transparent_paper: &str,
 ) -> Result<TransparentPaper, FullyFoldTransparentPaperError> {
+    fold_n_times(transparent_paper, usize::MAX).map_err(Into::into)
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum FullyFoldTransparentPaperError {
+    #[error(transparent)]
+    FoldNTimes(#[from] FoldNTimesError),
+}
+
+pub fn fold_n_times(
+    transparent_paper: &str,
+    n: usize,
+) -> Result<TransparentPaper, FoldNTimesError> {
     let mut transparent_paper = TransparentPaper::from_str(transparent_paper)?;
-    while !transparent_paper.instructions.is_empty() {
+    for _ in 0..n {
+        if transparent_paper.instructions.is_empty() {
+            break;
+        }
         transparent_paper.fold();
     }
     Ok(transparent_paper)
 }
 
+const LETTER_WIDTH: usize = 4;
+const LETTER_HEIGHT: usize = 6;
+const LETTER_GLYPHS: [(char, &str); 17] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('I', ".###..#...#...#...#..###"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+pub fn read_folded_code(transparent_paper: &str) -> Result<String, ReadFoldedCodeError> {
+    read_letters(&fully_fold_transparent_paper(transparent_paper)?)
+}
+
+fn read_letters(transparent_paper: &TransparentPaper) -> Result<String, ReadFoldedCodeError> {
+    // every letter is four pixels wide and followed by an empty column
+    (0..((transparent_paper.size.width + 1) / (LETTER_WIDTH + 1)))
+        .map(|letter_index| {
+            let glyph = (0..LETTER_HEIGHT)
+                .flat_map(|y| {
+                    (0..LETTER_WIDTH).map(move |x| Position {
+                        x: letter_index * (LETTER_WIDTH + 1) + x,
+                        y,
+                    })
+                })
+                .map(|position| {
+                    if transparent_paper.marked_dot_positions.contains(&position) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>();
+            LETTER_GLYPHS
+                .iter()
+                .find(|(_, letter_glyph)| *letter_glyph == glyph)
+                .map(|(letter, _)| *letter)
+                .ok_or(ReadFoldedCodeError::UnrecognizedGlyph(letter_index))
+        })
+        .collect()
+}
+
 #[derive(Debug, Error, Eq, PartialEq)]
-pub enum FullyFoldTransparentPaperError {
+pub enum ReadFoldedCodeError {
+    #[error(transparent)]
+    FullyFoldTransparentPaper(#[from] FullyFoldTransparentPaperError),
+    #[error("Glyph no. {0} is not a recognized letter")]
+    UnrecognizedGlyph(usize),
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum FoldNTimesError {
     #[error("Could not parse transparent paper ({0})")]
     TransparentPaperFromStr(#[from] TransparentPaperFromStrError),
 }
@@ -127,7 +263,11 @@ impl TransparentPaper {
                     }
                     collections
                 });
-            self.size = Self::calculate_size(&self.marked_dot_positions);
+            // the fold line, not the remaining dots, determines the new size
+            match instruction {
+                FoldInstruction::FoldAlongX(x_fold_index) => self.size.width = x_fold_index,
+                FoldInstruction::FoldAlongY(y_fold_index) => self.size.height = y_fold_index,
+            }
         }
     }
 
@@ -268,7 +408,7 @@ pub enum PositionFromStrError {
     UnexpectedCountOfElements(usize),
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 struct Size {
     width: usize,
     height: usize,
@@ -313,9 +453,7 @@ mod tests {
     #[test]
     fn count_dots_visible_after_folding_once_should_return_17() {
         // given
-        let input = "6,10\r\n0,14\r\n9,10\r\n0,3\r\n10,4\r\n4,11\r\n6,0\r\n6,12\r\n4,1\r\n\
-                            0,13\r\n10,12\r\n3,4\r\n3,0\r\n8,4\r\n1,10\r\n2,14\r\n8,10\r\n9,0\r\n\
-                            \r\nfold along y=7\r\nfold along x=5";
+        let input = EXAMPLE;
 
         // when
         let dot_count_after_fold = count_dots_visible_after_folding_once(input);
@@ -323,4 +461,86 @@ mod tests {
         // then
         assert_eq!(dot_count_after_fold, Ok(17));
     }
+
+    #[test]
+    fn fold_n_times_with_two_folds_should_return_5_by_7_paper() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let transparent_paper = fold_n_times(input, 2).unwrap();
+
+        // then
+        assert_eq!(
+            transparent_paper.size,
+            Size {
+                width: 5,
+                height: 7
+            }
+        );
+        assert_eq!(transparent_paper.marked_dot_positions.len(), 16);
+        assert_eq!(
+            transparent_paper.to_string(),
+            "#####\n#...#\n#...#\n#...#\n#####\n.....\n.....\n\n"
+        );
+    }
+
+    #[test]
+    fn read_folded_code_should_return_face() {
+        // given
+        let picture = [
+            "####..##...##..####",
+            "#....#..#.#..#.#...",
+            "###..#..#.#....###.",
+            "#....####.#....#...",
+            "#....#..#.#..#.#...",
+            "#....#..#..##..####",
+        ];
+        let mut input = String::new();
+        for (y, line) in picture.iter().enumerate() {
+            for (x, character) in line.chars().enumerate() {
+                if character == '#' {
+                    input.push_str(&format!("{},{}\r\n", x, y));
+                }
+            }
+        }
+        input.push_str("\r\nfold along y=6");
+
+        // when
+        let folded_code = read_folded_code(&input);
+
+        // then
+        assert_eq!(folded_code, Ok("FACE".to_string()));
+    }
+
+    #[test]
+    fn read_folded_code_with_unknown_glyph_should_fail() {
+        // given
+        let input = "0,0\r\n1,1\r\n2,2\r\n3,3\r\n3,5\r\n\r\nfold along y=6";
+
+        // when
+        let folded_code = read_folded_code(input);
+
+        // then
+        assert_eq!(folded_code, Err(ReadFoldedCodeError::UnrecognizedGlyph(0)));
+    }
+
+    #[test]
+    fn write_rendered_should_write_folded_transparent_paper_to_file() {
+        // given
+        let folded_transparent_paper = fully_fold_transparent_paper(EXAMPLE).unwrap();
+        let file_path =
+            std::env::temp_dir().join(format!("aoc2021-day13-output-{}", std::process::id()));
+
+        // when
+        let written = write_rendered(file_path.to_str().unwrap(), &folded_transparent_paper);
+
+        // then
+        assert!(written.is_ok());
+        assert_eq!(
+            std::fs::read_to_string(&file_path).unwrap(),
+            folded_transparent_paper.to_string()
+        );
+        std::fs::remove_file(&file_path).unwrap();
+    }
 }
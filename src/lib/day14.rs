@@ -1,38 +1,48 @@
 use std::collections::HashMap;
+use std::hash::Hash;
 use std::str::FromStr;
+use std::time::Instant;
 
-use clap::{App, Arg, ArgMatches, SubCommand};
+use clap::{App, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_puzzle_part_time_two,
+    clap_arg_repeat, clap_arg_time, part_from_matches, print_answer, print_elapsed_time,
+    read_input_contents, solve_repeatedly, InputShapeError, Part, ReadFileContentsError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day14";
+pub const ABOUT: &str = "My solution for Day 14: Extended Polymerization";
+
+pub const EXAMPLE: &str = "NNCB\r\n\r\nCH -> B\r\nHH -> N\r\nCB -> H\r\nNH -> C\r\nHB -> C\r\n\
+    HC -> B\r\nHN -> C\r\nNN -> C\r\nBH -> H\r\nNC -> B\r\nNB -> B\r\n\
+    BN -> B\r\nBB -> N\r\nBC -> B\r\nCC -> N\r\nCN -> C";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 14: Extended Polymerization")
-        .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day14-input"),
-        )
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day14-input"))
         .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day14Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day14Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    let step_count = match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => 40,
-        _ => 10,
+    let solving_start = Instant::now();
+    let (part, step_count) = match part_from_matches(matches) {
+        Part::Two => (2, 40),
+        Part::One => (1, 10),
     };
-    let processed_polymer_character_count =
-        process_polymer_pair_insertion_rules(&file_contents, step_count)?;
+    let processed_polymer_character_count = solve_repeatedly(matches, || {
+        process_polymer_pair_insertion_rules(&file_contents, step_count)
+    })?;
     let (most_common, least_common) = processed_polymer_character_count
         .into_iter()
         .fold(None, |output, next| match output {
@@ -51,13 +61,20 @@ pub fn handle(matches: &ArgMatches) -> Result<(), Day14Error> {
             )),
         })
         .unwrap();
-    println!(
-        "The answer to the puzzle with {} steps is {:?} - {:?} = {}",
-        step_count,
-        most_common,
-        least_common,
-        most_common.1 - least_common.1
+    print_answer(
+        matches,
+        14,
+        part,
+        most_common.1 - least_common.1,
+        format!(
+            "The answer to the puzzle with {} steps is {:?} - {:?} = {}",
+            step_count,
+            most_common,
+            least_common,
+            most_common.1 - least_common.1
+        ),
     );
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -73,6 +90,28 @@ pub fn process_polymer_pair_insertion_rules(
     instructions: &str,
     step_count: u128,
 ) -> Result<HashMap<char, u128>, ProcessPolymerPairInsertionRulesError> {
+    process_polymer_with_length(instructions, step_count)
+        .map(|(character_counts, _)| character_counts)
+        .map_err(|error| match error {
+            ProcessPolymerWithLengthError::CountOverflow => {
+                ProcessPolymerPairInsertionRulesError::CountOverflow
+            }
+            error => error.into(),
+        })
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum ProcessPolymerPairInsertionRulesError {
+    #[error(transparent)]
+    ProcessPolymerWithLength(#[from] ProcessPolymerWithLengthError),
+    #[error("A character count overflowed")]
+    CountOverflow,
+}
+
+pub fn process_polymer_with_length(
+    instructions: &str,
+    step_count: u128,
+) -> Result<(HashMap<char, u128>, u128), ProcessPolymerWithLengthError> {
     let polymer_instructions = PolymerInstructions::from_str(instructions)?;
 
     let (mut bucket_pair_counting_map, optional_last_character) =
@@ -106,40 +145,51 @@ pub fn process_polymer_pair_insertion_rules(
             let mut last_first_pair_character = pair.0;
             for pair_insertion_rule in &polymer_instructions.pair_insertion_rules {
                 if pair_insertion_rule.search_str == pair_search_str {
-                    let insert_character = pair_insertion_rule
-                        .insert_str
-                        .chars()
-                        .collect::<Vec<char>>()[0];
-                    output
-                        .entry((last_first_pair_character, insert_character))
-                        .and_modify(|c| *c += count)
-                        .or_insert(count);
-                    last_first_pair_character = insert_character;
+                    for insert_character in pair_insertion_rule.insert_str.chars() {
+                        add_count(
+                            &mut output,
+                            (last_first_pair_character, insert_character),
+                            count,
+                        )?;
+                        last_first_pair_character = insert_character;
+                    }
                 }
             }
-            output
-                .entry((last_first_pair_character, pair.1))
-                .and_modify(|c| *c += count)
-                .or_insert(count);
+            add_count(&mut output, (last_first_pair_character, pair.1), count)?;
         }
         bucket_pair_counting_map = output;
     }
 
     let mut output = HashMap::new();
     for ((character, _), counter) in bucket_pair_counting_map.into_iter() {
-        output
-            .entry(character)
-            .and_modify(|c| *c += counter)
-            .or_insert(counter);
+        add_count(&mut output, character, counter)?;
     }
+    let length = output
+        .values()
+        .try_fold(0u128, |length, count| length.checked_add(*count))
+        .ok_or(ProcessPolymerWithLengthError::CountOverflow)?;
 
-    Ok(output)
+    Ok((output, length))
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
-pub enum ProcessPolymerPairInsertionRulesError {
+pub enum ProcessPolymerWithLengthError {
     #[error("Could not parse polymer instructions ({0})")]
     PolymerInstructionsFromStr(#[from] PolymerInstructionsFromStrError),
+    #[error("A character count overflowed")]
+    CountOverflow,
+}
+
+fn add_count<K: Eq + Hash>(
+    counts: &mut HashMap<K, u128>,
+    key: K,
+    count: u128,
+) -> Result<(), ProcessPolymerWithLengthError> {
+    let bucket = counts.entry(key).or_insert(0);
+    *bucket = bucket
+        .checked_add(count)
+        .ok_or(ProcessPolymerWithLengthError::CountOverflow)?;
+    Ok(())
 }
 
 #[derive(Clone)]
@@ -173,8 +223,7 @@ impl FromStr for PolymerInstructions {
             },
         );
         Ok(Self {
-            polymer_template: optional_first_line
-                .ok_or(PolymerInstructionsFromStrError::NoLinesInInput)?,
+            polymer_template: optional_first_line.ok_or(InputShapeError::Empty)?,
             pair_insertion_rules: pair_insertions
                 .into_iter()
                 .collect::<Result<Vec<PairInsertionRule>, PolymerInstructionsFromStrError>>()?,
@@ -184,8 +233,8 @@ impl FromStr for PolymerInstructions {
 
 #[derive(Debug, Error, Eq, PartialEq)]
 pub enum PolymerInstructionsFromStrError {
-    #[error("There were no lines to parse")]
-    NoLinesInInput,
+    #[error("Input has an unexpected shape ({0})")]
+    InputShape(#[from] InputShapeError),
     #[error("Could not parse pair insertion rule from str \"{0}\" ({1})")]
     PairInsertionRuleFromStr(String, #[source] PairInsertionRuleFromStrError),
 }
@@ -207,6 +256,9 @@ impl FromStr for PairInsertionRule {
                 .map_err(|_: Vec<&str>| {
                     PairInsertionRuleFromStrError::NotThreeElements(s.to_string())
                 })?;
+        if k[2].is_empty() {
+            return Err(PairInsertionRuleFromStrError::EmptyInsertStr(s.to_string()));
+        }
         Ok(Self {
             search_str: k[0].to_string(),
             insert_str: k[2].to_string(),
@@ -218,6 +270,8 @@ impl FromStr for PairInsertionRule {
 pub enum PairInsertionRuleFromStrError {
     #[error("Pair insertion rule does not have three elements \"{0}\"")]
     NotThreeElements(String),
+    #[error("Pair insertion rule does not insert anything \"{0}\"")]
+    EmptyInsertStr(String),
 }
 
 #[cfg(test)]
@@ -227,9 +281,7 @@ mod tests {
     #[test]
     fn process_polymer_pair_insertion_rules_should_return_b_1749_c_298_h_161_n_865() {
         // given
-        let input = "NNCB\r\n\r\nCH -> B\r\nHH -> N\r\nCB -> H\r\nNH -> C\r\nHB -> C\r\n\
-                            HC -> B\r\nHN -> C\r\nNN -> C\r\nBH -> H\r\nNC -> B\r\nNB -> B\r\n\
-                            BN -> B\r\nBB -> N\r\nBC -> B\r\nCC -> N\r\nCN -> C";
+        let input = EXAMPLE;
 
         // when
         let processed_polymer = process_polymer_pair_insertion_rules(input, 10);
@@ -245,9 +297,7 @@ mod tests {
     #[test]
     fn process_polymer_pair_insertion_rules_should_return_b_2192039569602_h_3849876073() {
         // given
-        let input = "NNCB\r\n\r\nCH -> B\r\nHH -> N\r\nCB -> H\r\nNH -> C\r\nHB -> C\r\n\
-                            HC -> B\r\nHN -> C\r\nNN -> C\r\nBH -> H\r\nNC -> B\r\nNB -> B\r\n\
-                            BN -> B\r\nBB -> N\r\nBC -> B\r\nCC -> N\r\nCN -> C";
+        let input = EXAMPLE;
 
         // when
         let processed_polymer = process_polymer_pair_insertion_rules(input, 40);
@@ -261,9 +311,7 @@ mod tests {
     #[test]
     fn process_polymer_pair_insertion_rules_should_return_ncnbchb() {
         // given
-        let input = "NNCB\r\n\r\nCH -> B\r\nHH -> N\r\nCB -> H\r\nNH -> C\r\nHB -> C\r\n\
-                            HC -> B\r\nHN -> C\r\nNN -> C\r\nBH -> H\r\nNC -> B\r\nNB -> B\r\n\
-                            BN -> B\r\nBB -> N\r\nBC -> B\r\nCC -> N\r\nCN -> C";
+        let input = EXAMPLE;
 
         // when
         let processed_polymer = process_polymer_pair_insertion_rules(input, 1);
@@ -279,9 +327,7 @@ mod tests {
     #[test]
     fn process_polymer_pair_insertion_rules_should_return_nbccnbbbcbhcb() {
         // given
-        let input = "NNCB\r\n\r\nCH -> B\r\nHH -> N\r\nCB -> H\r\nNH -> C\r\nHB -> C\r\n\
-                            HC -> B\r\nHN -> C\r\nNN -> C\r\nBH -> H\r\nNC -> B\r\nNB -> B\r\n\
-                            BN -> B\r\nBB -> N\r\nBC -> B\r\nCC -> N\r\nCN -> C";
+        let input = EXAMPLE;
 
         // when
         let processed_polymer = process_polymer_pair_insertion_rules(input, 2);
@@ -297,9 +343,7 @@ mod tests {
     #[test]
     fn process_polymer_pair_insertion_rules_should_return_nbbbcnccnbbnbnbbchbhhbchb() {
         // given
-        let input = "NNCB\r\n\r\nCH -> B\r\nHH -> N\r\nCB -> H\r\nNH -> C\r\nHB -> C\r\n\
-                            HC -> B\r\nHN -> C\r\nNN -> C\r\nBH -> H\r\nNC -> B\r\nNB -> B\r\n\
-                            BN -> B\r\nBB -> N\r\nBC -> B\r\nCC -> N\r\nCN -> C";
+        let input = EXAMPLE;
 
         // when
         let processed_polymer = process_polymer_pair_insertion_rules(input, 3);
@@ -316,9 +360,7 @@ mod tests {
     fn process_polymer_pair_insertion_rules_should_return_nbbnbnbbccnbcnccnbbnbbnbbbnbbnbbcbhcbhhbhcbbcbhcb(
     ) {
         // given
-        let input = "NNCB\r\n\r\nCH -> B\r\nHH -> N\r\nCB -> H\r\nNH -> C\r\nHB -> C\r\n\
-                            HC -> B\r\nHN -> C\r\nNN -> C\r\nBH -> H\r\nNC -> B\r\nNB -> B\r\n\
-                            BN -> B\r\nBB -> N\r\nBC -> B\r\nCC -> N\r\nCN -> C";
+        let input = EXAMPLE;
 
         // when
         let processed_polymer = process_polymer_pair_insertion_rules(input, 4);
@@ -330,4 +372,91 @@ mod tests {
         assert_eq!(processed_polymer.get(&'B'), Some(&23));
         assert_eq!(processed_polymer.get(&'H'), Some(&5));
     }
+
+    #[test]
+    fn process_polymer_with_length_should_return_length_3073() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let processed_polymer = process_polymer_with_length(input, 10);
+
+        // then
+        assert_eq!(processed_polymer.map(|(_, length)| length), Ok(3073));
+    }
+
+    #[test]
+    fn process_polymer_pair_insertion_rules_with_too_many_steps_should_fail() {
+        // given
+        let input = "NN\r\n\r\nNN -> N";
+
+        // when
+        let processed_polymer = process_polymer_pair_insertion_rules(input, 200);
+
+        // then
+        assert_eq!(
+            processed_polymer,
+            Err(ProcessPolymerPairInsertionRulesError::CountOverflow)
+        );
+        assert_eq!(
+            process_polymer_with_length(input, 126),
+            Ok((HashMap::from([('N', (1 << 126) + 1)]), (1 << 126) + 1))
+        );
+    }
+
+    #[test]
+    fn process_polymer_with_length_should_support_multi_character_insertions() {
+        // given
+        let input = "AB\r\n\r\nAB -> XY\r\nXY -> Z";
+
+        // when
+        let processed_polymer = process_polymer_with_length(input, 2);
+
+        // then
+        assert_eq!(
+            processed_polymer,
+            Ok((
+                HashMap::from([('A', 1), ('X', 1), ('Z', 1), ('Y', 1), ('B', 1)]),
+                5
+            ))
+        );
+    }
+
+    #[test]
+    fn pair_insertion_rule_with_empty_insert_str_should_fail() {
+        // given
+        let input = "AB -> ";
+
+        // when
+        let pair_insertion_rule = PairInsertionRule::from_str(input);
+
+        // then
+        assert_eq!(
+            pair_insertion_rule.err(),
+            Some(PairInsertionRuleFromStrError::EmptyInsertStr(
+                input.to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn process_polymer_pair_insertion_rules_with_empty_input_should_fail_with_input_shape() {
+        // given
+        let input = "";
+
+        // when
+        let character_counts = process_polymer_pair_insertion_rules(input, 10);
+
+        // then
+        assert_eq!(
+            character_counts,
+            Err(
+                ProcessPolymerPairInsertionRulesError::ProcessPolymerWithLength(
+                    ProcessPolymerWithLengthError::PolymerInstructionsFromStr(
+                        PolymerInstructionsFromStrError::InputShape(InputShapeError::Empty)
+                    )
+                )
+            )
+        );
+    }
 }
@@ -1,42 +1,68 @@
-use std::num::ParseIntError;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 use std::str::FromStr;
+use std::time::Instant;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_puzzle_part_time_two,
+    clap_arg_repeat, clap_arg_time, neighbors4, parse_digit_grid, part_from_matches, print_answer,
+    print_elapsed_time, read_input_contents, solve_repeatedly, GridParseError, Part,
+    ReadFileContentsError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day15";
+pub const ABOUT: &str = "My solution for Day 15: Chiton";
+
+pub const EXAMPLE: &str = "1163751742\r\n1381373672\r\n2136511328\r\n3694931569\r\n7463417111\r\n\
+    1319128137\r\n1359912421\r\n3125421639\r\n1293138521\r\n2311944581";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 15: Chiton")
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day15-input"))
+        .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
         .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day15-input"),
+            Arg::with_name("astar")
+                .long("astar")
+                .help("uses A* with the manhattan distance instead of Dijkstra"),
         )
-        .arg(clap_arg_puzzle_part_time_two())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day15Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day15Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    let multiply_map = match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => (5, 5),
-        _ => (1, 1),
+    let solving_start = Instant::now();
+    let (part, tile_factor) = match part_from_matches(matches) {
+        Part::Two => (2, 5),
+        Part::One => (1, 1),
     };
-    let lowest_total_risk_of_any_path =
-        calculate_lowest_total_risk_of_any_path(&file_contents, multiply_map)?;
-    println!(
-        "The lowest total risk of any path is {} with a map multiplied {:?}.",
-        lowest_total_risk_of_any_path, multiply_map
+    let lowest_total_risk_of_any_path = if matches.is_present("astar") {
+        solve_repeatedly(matches, || {
+            lowest_total_risk_astar(&file_contents, tile_factor)
+        })?
+    } else {
+        solve_repeatedly(matches, || lowest_total_risk(&file_contents, tile_factor))?
+    };
+    print_answer(
+        matches,
+        15,
+        part,
+        lowest_total_risk_of_any_path,
+        format!(
+            "The lowest total risk of any path is {} with a map tiled {}x{}.",
+            lowest_total_risk_of_any_path, tile_factor, tile_factor
+        ),
     );
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -44,79 +70,117 @@ pub fn handle(matches: &ArgMatches) -> Result<(), Day15Error> {
 pub enum Day15Error {
     #[error("Could not read file contents of \"{0:?}\" ({1})")]
     ReadFileContents(Option<String>, #[source] ReadFileContentsError),
-    #[error("Could not calculate lowest total risk of any path ({0})")]
+    #[error("Could not calculate lowest total risk ({0})")]
+    LowestTotalRisk(#[from] LowestTotalRiskError),
+    #[error("Could not calculate lowest total risk with A* ({0})")]
+    LowestTotalRiskAstar(#[from] LowestTotalRiskAstarError),
+}
+
+pub fn lowest_total_risk(
+    risk_level_map: &str,
+    tile_factor: usize,
+) -> Result<u128, LowestTotalRiskError> {
+    Ok(calculate_lowest_total_risk_of_any_path(
+        risk_level_map,
+        (tile_factor, tile_factor),
+    )?)
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum LowestTotalRiskError {
+    #[error(transparent)]
     CalculateLowestTotalRiskOfAnyPath(#[from] CalculateLowestTotalRiskOfAnyPathError),
 }
 
+pub fn lowest_total_risk_astar(
+    risk_level_map: &str,
+    tile_factor: usize,
+) -> Result<u128, LowestTotalRiskAstarError> {
+    let risk_level_map =
+        RiskLevelMap::from_str(risk_level_map)?.multiply((tile_factor, tile_factor));
+    let (end_x, end_y) = (
+        risk_level_map
+            .map
+            .first()
+            .map_or(0, Vec::len)
+            .saturating_sub(1),
+        risk_level_map.map.len().saturating_sub(1),
+    );
+    search_lowest_total_risk(&risk_level_map, |(x, y)| {
+        (end_x.abs_diff(x) + end_y.abs_diff(y)) as u128
+    })
+    .ok_or(LowestTotalRiskAstarError::EmptyRiskLevelMap)
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum LowestTotalRiskAstarError {
+    #[error("Could not parse risk level map from string ({0})")]
+    RiskLevelMapFromStr(#[from] RiskLevelMapFromStrError),
+    #[error("Risk level map is empty")]
+    EmptyRiskLevelMap,
+}
+
 pub fn calculate_lowest_total_risk_of_any_path(
     risk_level_map: &str,
     multiply_map: (usize, usize),
 ) -> Result<u128, CalculateLowestTotalRiskOfAnyPathError> {
     let risk_level_map = RiskLevelMap::from_str(risk_level_map)?.multiply(multiply_map);
 
+    search_lowest_total_risk(&risk_level_map, |_| 0)
+        .ok_or(CalculateLowestTotalRiskOfAnyPathError::EmptyRiskLevelMap)
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum CalculateLowestTotalRiskOfAnyPathError {
+    #[error("Could not parse risk level map from string ({0})")]
+    RiskLevelMapFromStr(#[from] RiskLevelMapFromStrError),
+    #[error("Risk level map is empty")]
+    EmptyRiskLevelMap,
+}
+
+// The heuristic has to be admissible and consistent, so |_| 0 makes this a plain Dijkstra
+fn search_lowest_total_risk<H: Fn((usize, usize)) -> u128>(
+    risk_level_map: &RiskLevelMap,
+    heuristic: H,
+) -> Option<u128> {
+    if risk_level_map.map.is_empty() || risk_level_map.map[0].is_empty() {
+        return None;
+    }
+
     let start: (usize, usize) = (0, 0);
     let end: (usize, usize) = (
         risk_level_map.map[0].len() - 1,
         risk_level_map.map.len() - 1,
     );
 
-    // Dijkstra takes around 3 minutes for the second part on my machine, but that's good enough for me
-
     let mut distance = risk_level_map
         .map
         .iter()
         .map(|line| vec![u128::MAX; line.len()])
         .collect::<Vec<Vec<u128>>>();
-    let mut predecessor = risk_level_map
-        .map
-        .iter()
-        .map(|line| vec![None; line.len()])
-        .collect::<Vec<Vec<Option<(usize, usize)>>>>();
     distance[start.1][start.0] = 0;
-    let mut open = (0..risk_level_map.map.len())
-        .flat_map(|y| (0..risk_level_map.map[y].len()).map(move |x| (x, y)))
-        .collect::<Vec<(usize, usize)>>();
-    while !open.is_empty() {
-        let current = open.remove(
-            open.iter()
-                .enumerate()
-                .reduce(|a, b| {
-                    if distance[a.1 .1][a.1 .0] < distance[b.1 .1][b.1 .0] {
-                        a
-                    } else {
-                        b
-                    }
-                })
-                .unwrap()
-                .0,
-        );
-        let mut distance_update = |neighbour: (usize, usize)| {
-            if open.contains(&neighbour) {
-                let alternative = distance[current.1][current.0]
-                    + risk_level_map.map[neighbour.1][neighbour.0] as u128;
-                if alternative < distance[neighbour.1][neighbour.0] {
-                    distance[neighbour.1][neighbour.0] = alternative;
-                    predecessor[neighbour.1][neighbour.0] = Some(current);
-                }
-            }
-        };
-        if current.0 > 0 {
-            distance_update((current.0 - 1, current.1));
+    let mut open = BinaryHeap::from([Reverse((heuristic(start), 0, start))]);
+    while let Some(Reverse((_, current_distance, current))) = open.pop() {
+        if current == end {
+            break;
+        }
+        if current_distance > distance[current.1][current.0] {
+            continue;
         }
-        if current.1 > 0 {
-            distance_update((current.0, current.1 - 1));
+        for (x, y) in neighbors4(current.0, current.1, end.0 + 1, end.1 + 1) {
+            let alternative = current_distance + risk_level_map.map[y][x] as u128;
+            if alternative < distance[y][x] {
+                distance[y][x] = alternative;
+                open.push(Reverse((
+                    alternative + heuristic((x, y)),
+                    alternative,
+                    (x, y),
+                )));
+            }
         }
-        distance_update((current.0, current.1 + 1));
-        distance_update((current.0 + 1, current.1));
     }
 
-    Ok(distance[end.1][end.0])
-}
-
-#[derive(Debug, Error, Eq, PartialEq)]
-pub enum CalculateLowestTotalRiskOfAnyPathError {
-    #[error("Could not parse risk level map from string ({0})")]
-    RiskLevelMapFromStr(#[from] RiskLevelMapFromStrError),
+    Some(distance[end.1][end.0])
 }
 
 #[derive(Debug)]
@@ -169,26 +233,15 @@ impl FromStr for RiskLevelMap {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Self {
-            map: s
-                .lines()
-                .map(|line| {
-                    line.chars()
-                        .map(|character| {
-                            character.to_string().parse::<u8>().map_err(|error| {
-                                RiskLevelMapFromStrError::ParseInt(character, error)
-                            })
-                        })
-                        .collect::<Result<Vec<u8>, RiskLevelMapFromStrError>>()
-                })
-                .collect::<Result<Vec<Vec<u8>>, RiskLevelMapFromStrError>>()?,
+            map: parse_digit_grid(s)?,
         })
     }
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
 pub enum RiskLevelMapFromStrError {
-    #[error("Could not parse '{0}' ({1})")]
-    ParseInt(char, #[source] ParseIntError),
+    #[error("Could not parse digit grid ({0})")]
+    ParseDigitGrid(#[from] GridParseError),
 }
 
 #[cfg(test)]
@@ -198,8 +251,7 @@ mod tests {
     #[test]
     fn calculate_lowest_total_risk_of_any_path_should_return_40() {
         // given
-        let input = "1163751742\r\n1381373672\r\n2136511328\r\n3694931569\r\n7463417111\r\n\
-                            1319128137\r\n1359912421\r\n3125421639\r\n1293138521\r\n2311944581";
+        let input = EXAMPLE;
 
         // when
         let lowest_total_risk = calculate_lowest_total_risk_of_any_path(input, (1, 1));
@@ -211,8 +263,7 @@ mod tests {
     #[test]
     fn calculate_lowest_total_risk_of_any_path_should_return_315() {
         // given
-        let input = "1163751742\r\n1381373672\r\n2136511328\r\n3694931569\r\n7463417111\r\n\
-                            1319128137\r\n1359912421\r\n3125421639\r\n1293138521\r\n2311944581";
+        let input = EXAMPLE;
 
         // when
         let lowest_total_risk = calculate_lowest_total_risk_of_any_path(input, (5, 5));
@@ -220,4 +271,64 @@ mod tests {
         // then
         assert_eq!(lowest_total_risk, Ok(315));
     }
+
+    #[test]
+    fn lowest_total_risk_with_tile_factor_1_should_return_40() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let lowest_total_risk = lowest_total_risk(input, 1);
+
+        // then
+        assert_eq!(lowest_total_risk, Ok(40));
+    }
+
+    #[test]
+    fn lowest_total_risk_with_tile_factor_5_should_return_315() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let lowest_total_risk = lowest_total_risk(input, 5);
+
+        // then
+        assert_eq!(lowest_total_risk, Ok(315));
+    }
+
+    #[test]
+    fn lowest_total_risk_with_tile_factor_0_should_fail() {
+        // given
+        let input = "1163751742\r\n1381373672";
+
+        // when
+        let lowest_total_risk = lowest_total_risk(input, 0);
+
+        // then
+        assert_eq!(
+            lowest_total_risk,
+            Err(LowestTotalRiskError::CalculateLowestTotalRiskOfAnyPath(
+                CalculateLowestTotalRiskOfAnyPathError::EmptyRiskLevelMap
+            ))
+        );
+    }
+
+    #[test]
+    fn lowest_total_risk_astar_should_agree_with_dijkstra() {
+        // given
+        let input = EXAMPLE;
+
+        for tile_factor in [1, 5] {
+            // when
+            let lowest_total_risk_astar = lowest_total_risk_astar(input, tile_factor);
+
+            // then
+            assert_eq!(
+                lowest_total_risk_astar.ok(),
+                lowest_total_risk(input, tile_factor).ok()
+            );
+        }
+        assert_eq!(lowest_total_risk_astar(input, 1), Ok(40));
+        assert_eq!(lowest_total_risk_astar(input, 5), Ok(315));
+    }
 }
@@ -1,45 +1,89 @@
+use std::fmt::Display;
 use std::str::FromStr;
+use std::time::Instant;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_puzzle_part_time_two,
+    clap_arg_repeat, clap_arg_time, part_from_matches, print_answer, print_elapsed_time,
+    read_input_contents, solve_repeatedly, InputShapeError, Part, ReadFileContentsError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day16";
+pub const ABOUT: &str = "My solution for Day 16: Packet Decoder";
+
+pub const EXAMPLE: &str = "C0015000016115A2E0802F182340";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 16: Packet Decoder")
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day16-input"))
+        .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
         .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day16-input"),
+            Arg::with_name("strict")
+                .long("strict")
+                .help("rejects transmissions with non-zero bits after the packet"),
+        )
+        .arg(
+            Arg::with_name("describe")
+                .long("describe")
+                .help("prints the decoded packet tree to stderr"),
         )
-        .arg(clap_arg_puzzle_part_time_two())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day16Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day16Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => {
-            let value_of_packet = calculate_value_of_packet(&file_contents)?;
-            println!("The value of the packet is {}.", value_of_packet);
+    let solving_start = Instant::now();
+    let strict = matches.is_present("strict");
+    let describe = matches.is_present("describe");
+    let mut optional_description = None;
+    let mut inspect_packet = |packet: &dyn Display| {
+        if describe {
+            optional_description = Some(packet.to_string());
         }
-        _ => {
-            let sum_of_packet_version_numbers =
-                calculate_sum_of_packet_version_numbers(&file_contents)?;
-            println!(
-                "The sum of the packet version numbers is {}.",
-                sum_of_packet_version_numbers
+    };
+    match part_from_matches(matches) {
+        Part::Two => {
+            let value_of_packet = solve_repeatedly(matches, || {
+                calculate_value_of_packet(&file_contents, strict, &mut inspect_packet)
+            })?;
+            print_answer(
+                matches,
+                16,
+                2,
+                value_of_packet,
+                format!("The value of the packet is {}.", value_of_packet),
+            );
+        }
+        Part::One => {
+            let sum_of_packet_version_numbers = solve_repeatedly(matches, || {
+                calculate_sum_of_packet_version_numbers(&file_contents, strict, &mut inspect_packet)
+            })?;
+            print_answer(
+                matches,
+                16,
+                1,
+                sum_of_packet_version_numbers,
+                format!(
+                    "The sum of the packet version numbers is {}.",
+                    sum_of_packet_version_numbers
+                ),
             );
         }
     };
+    if let Some(description) = optional_description {
+        eprintln!("{}", description);
+    }
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -55,8 +99,12 @@ pub enum Day16Error {
 
 pub fn calculate_sum_of_packet_version_numbers(
     bits_transmission: &str,
+    strict: bool,
+    inspect_packet: &mut dyn FnMut(&dyn Display),
 ) -> Result<u128, CalculateSumOfPacketVersionNumbersError> {
-    Ok(Packet::from_str(bits_transmission)?.sum_versions())
+    let packet = parse_packet(bits_transmission, strict)?;
+    inspect_packet(&packet);
+    Ok(packet.sum_versions())
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
@@ -67,8 +115,12 @@ pub enum CalculateSumOfPacketVersionNumbersError {
 
 pub fn calculate_value_of_packet(
     bits_transmission: &str,
+    strict: bool,
+    inspect_packet: &mut dyn FnMut(&dyn Display),
 ) -> Result<u128, CalculateValueOfPacketError> {
-    Ok(Packet::from_str(bits_transmission)?.value())
+    let packet = parse_packet(bits_transmission, strict)?;
+    inspect_packet(&packet);
+    Ok(packet.value())
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
@@ -77,6 +129,23 @@ pub enum CalculateValueOfPacketError {
     PacketFromStr(#[from] PacketFromStrError),
 }
 
+pub fn evaluate(bits_transmission: &str) -> Result<(u128, u128), PacketFromStrError> {
+    let packet = Packet::from_str(bits_transmission)?;
+    Ok((packet.sum_versions(), packet.value()))
+}
+
+fn parse_packet(bits_transmission: &str, strict: bool) -> Result<Packet, PacketFromStrError> {
+    if strict {
+        Packet::from_str_strict(bits_transmission)
+    } else {
+        Packet::from_str(bits_transmission)
+    }
+}
+
+pub fn describe_packet(bits_transmission: &str) -> Result<String, PacketFromStrError> {
+    Ok(Packet::from_str(bits_transmission)?.to_string())
+}
+
 #[derive(Debug, Eq, PartialEq)]
 struct Packet {
     version: u8,
@@ -99,6 +168,29 @@ impl Packet {
     }
 }
 
+impl Display for Packet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} v{}", self.type_, self.version)?;
+        if let PacketType::Operator {
+            length, packets, ..
+        } = &self.type_
+        {
+            match length {
+                LengthType::TotalLengthOfAllSubPacketInBits(bits) => {
+                    write!(f, " ({} bits of sub-packets)", bits)?
+                }
+                LengthType::NumberOfSubPackets(count) => write!(f, " ({} sub-packets)", count)?,
+            }
+            for packet in packets {
+                for line in packet.to_string().lines() {
+                    write!(f, "\n  {}", line)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Eq, PartialEq)]
 enum PacketType {
     LiteralValue {
@@ -146,6 +238,15 @@ impl PacketType {
     }
 }
 
+impl Display for PacketType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PacketType::LiteralValue { value } => write!(f, "Literal {}", value),
+            PacketType::Operator { type_, .. } => write!(f, "Operator({})", type_),
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq)]
 enum OperatorType {
     Sum,
@@ -157,16 +258,50 @@ enum OperatorType {
     EqualTo,
 }
 
+impl Display for OperatorType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OperatorType::Sum => write!(f, "Sum"),
+            OperatorType::Product => write!(f, "Product"),
+            OperatorType::Minimum => write!(f, "Minimum"),
+            OperatorType::Maximum => write!(f, "Maximum"),
+            OperatorType::GreaterThan => write!(f, "GreaterThan"),
+            OperatorType::LessThan => write!(f, "LessThan"),
+            OperatorType::EqualTo => write!(f, "EqualTo"),
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq)]
 enum LengthType {
     TotalLengthOfAllSubPacketInBits(u128),
     NumberOfSubPackets(u128),
 }
 
-impl FromStr for Packet {
-    type Err = PacketFromStrError;
+impl Packet {
+    fn from_str_strict(s: &str) -> Result<Self, PacketFromStrError> {
+        let (packet, bit_buffer, characters) = Self::parse_leaving_rest(s)?;
+        let mut trailing_non_zero_bits = bit_buffer
+            .into_iter()
+            .filter(|bit| matches!(bit, Bit::One))
+            .count();
+        for character in characters {
+            trailing_non_zero_bits += character
+                .to_bits()?
+                .into_iter()
+                .filter(|bit| matches!(bit, Bit::One))
+                .count();
+        }
+        if trailing_non_zero_bits > 0 {
+            Err(PacketFromStrError::TrailingNonZeroBits(
+                trailing_non_zero_bits,
+            ))
+        } else {
+            Ok(packet)
+        }
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn parse_leaving_rest(s: &str) -> Result<(Self, Vec<Bit>, Vec<char>), PacketFromStrError> {
         fn parse<F: FnMut(usize) -> Result<Vec<Bit>, PacketFromStrError>>(
             poll_bits: &mut F,
         ) -> Result<(Packet, u128), PacketFromStrError> {
@@ -208,12 +343,12 @@ impl FromStr for Packet {
                     };
                     let length = match poll_bits(1)?[0] {
                         Bit::Zero => {
-                            let length = poll_bits(15)?.to_u128()? as u128;
+                            let length = poll_bits(15)?.to_u128()?;
                             read_bits += 16;
                             LengthType::TotalLengthOfAllSubPacketInBits(length)
                         }
                         Bit::One => {
-                            let packet_count = poll_bits(11)?.to_u128()? as u128;
+                            let packet_count = poll_bits(11)?.to_u128()?;
                             read_bits += 12;
                             LengthType::NumberOfSubPackets(packet_count)
                         }
@@ -255,7 +390,13 @@ impl FromStr for Packet {
             }
         }
 
-        let mut characters = s.chars().collect::<Vec<char>>();
+        let mut characters = s
+            .chars()
+            .filter(|character| !character.is_ascii_whitespace())
+            .collect::<Vec<char>>();
+        if characters.is_empty() {
+            return Err(InputShapeError::Empty.into());
+        }
         let mut bit_buffer = Vec::new();
 
         let mut poll_bits = |count: usize| -> Result<Vec<Bit>, PacketFromStrError> {
@@ -271,7 +412,16 @@ impl FromStr for Packet {
             Ok(bit_buffer.split_off_head(count))
         };
 
-        parse(&mut poll_bits).map(|(packet, _)| packet)
+        let (packet, _) = parse(&mut poll_bits)?;
+        Ok((packet, bit_buffer, characters))
+    }
+}
+
+impl FromStr for Packet {
+    type Err = PacketFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_leaving_rest(s).map(|(packet, _, _)| packet)
     }
 }
 
@@ -283,6 +433,10 @@ pub enum PacketFromStrError {
     VecBitToU128(#[from] VecBitToU128Error),
     #[error("Missing {0} bits in input")]
     MissingBitsInInput(usize),
+    #[error("Encountered {0} non-zero bits after the packet")]
+    TrailingNonZeroBits(usize),
+    #[error("Input has an unexpected shape ({0})")]
+    InputShape(#[from] InputShapeError),
 }
 
 trait ToBits {
@@ -470,10 +624,10 @@ mod tests {
         let input_d = "A0016C880162017C3686B18A3D4780";
 
         // when
-        let version_sum_a = calculate_sum_of_packet_version_numbers(input_a);
-        let version_sum_b = calculate_sum_of_packet_version_numbers(input_b);
-        let version_sum_c = calculate_sum_of_packet_version_numbers(input_c);
-        let version_sum_d = calculate_sum_of_packet_version_numbers(input_d);
+        let version_sum_a = calculate_sum_of_packet_version_numbers(input_a, false, &mut |_| {});
+        let version_sum_b = calculate_sum_of_packet_version_numbers(input_b, false, &mut |_| {});
+        let version_sum_c = calculate_sum_of_packet_version_numbers(input_c, false, &mut |_| {});
+        let version_sum_d = calculate_sum_of_packet_version_numbers(input_d, false, &mut |_| {});
 
         // then
         assert_eq!(version_sum_a, Ok(16));
@@ -482,6 +636,53 @@ mod tests {
         assert_eq!(version_sum_d, Ok(31));
     }
 
+    #[test]
+    fn calculate_sum_of_packet_version_numbers_should_not_evaluate_empty_operator() {
+        // given
+        let input = "0A000";
+
+        // when
+        let version_sum = calculate_sum_of_packet_version_numbers(input, false, &mut |_| {});
+
+        // then
+        assert_eq!(version_sum, Ok(0));
+    }
+
+    #[test]
+    fn calculate_value_of_packet_in_strict_mode_should_reject_trailing_non_zero_bits() {
+        // given
+        let input = "D2FE29";
+
+        // when
+        let lenient_value = calculate_value_of_packet(input, false, &mut |_| {});
+        let strict_value = calculate_value_of_packet(input, true, &mut |_| {});
+
+        // then
+        assert_eq!(lenient_value, Ok(2021));
+        assert_eq!(
+            strict_value,
+            Err(CalculateValueOfPacketError::PacketFromStr(
+                PacketFromStrError::TrailingNonZeroBits(1)
+            ))
+        );
+    }
+
+    #[test]
+    fn calculate_value_of_packet_should_inspect_the_solved_packet() {
+        // given
+        let input = "C200B40A82";
+        let mut inspected = Vec::new();
+
+        // when
+        let value = calculate_value_of_packet(input, false, &mut |packet| {
+            inspected.push(packet.to_string())
+        });
+
+        // then
+        assert_eq!(value, Ok(3));
+        assert_eq!(inspected, vec![describe_packet(input).unwrap()]);
+    }
+
     #[test]
     fn test_calculate_value_of_packet() {
         // given
@@ -495,14 +696,14 @@ mod tests {
         let input_h = "9C0141080250320F1802104A08";
 
         // when
-        let value_a = calculate_value_of_packet(input_a);
-        let value_b = calculate_value_of_packet(input_b);
-        let value_c = calculate_value_of_packet(input_c);
-        let value_d = calculate_value_of_packet(input_d);
-        let value_e = calculate_value_of_packet(input_e);
-        let value_f = calculate_value_of_packet(input_f);
-        let value_g = calculate_value_of_packet(input_g);
-        let value_h = calculate_value_of_packet(input_h);
+        let value_a = calculate_value_of_packet(input_a, false, &mut |_| {});
+        let value_b = calculate_value_of_packet(input_b, false, &mut |_| {});
+        let value_c = calculate_value_of_packet(input_c, false, &mut |_| {});
+        let value_d = calculate_value_of_packet(input_d, false, &mut |_| {});
+        let value_e = calculate_value_of_packet(input_e, false, &mut |_| {});
+        let value_f = calculate_value_of_packet(input_f, false, &mut |_| {});
+        let value_g = calculate_value_of_packet(input_g, false, &mut |_| {});
+        let value_h = calculate_value_of_packet(input_h, false, &mut |_| {});
 
         // then
         assert_eq!(value_a, Ok(3));
@@ -514,4 +715,184 @@ mod tests {
         assert_eq!(value_g, Ok(0));
         assert_eq!(value_h, Ok(1));
     }
+
+    #[test]
+    fn packet_from_str_strict_should_reject_trailing_non_zero_bits() {
+        // given
+        let input = "D2FE29";
+
+        // when
+        let strict_packet = Packet::from_str_strict(input);
+        let lenient_packet = Packet::from_str(input);
+
+        // then
+        assert_eq!(
+            strict_packet,
+            Err(PacketFromStrError::TrailingNonZeroBits(1))
+        );
+        assert_eq!(
+            lenient_packet,
+            Ok(Packet {
+                version: 6,
+                type_: PacketType::LiteralValue { value: 2021 }
+            })
+        );
+    }
+
+    #[test]
+    fn packet_from_str_strict_should_accept_zero_padding() {
+        // given
+        let input = "D2FE2800";
+
+        // when
+        let packet = Packet::from_str_strict(input);
+
+        // then
+        assert_eq!(
+            packet,
+            Ok(Packet {
+                version: 6,
+                type_: PacketType::LiteralValue { value: 2021 }
+            })
+        );
+    }
+
+    #[test]
+    fn describe_packet_should_return_literal_line() {
+        // given
+        let input = "D2FE28";
+
+        // when
+        let description = describe_packet(input);
+
+        // then
+        assert_eq!(description, Ok("Literal 2021 v6".to_string()));
+    }
+
+    #[test]
+    fn describe_packet_should_return_maximum_operator_with_three_children() {
+        // given
+        let input = "EE00D40C823060";
+
+        // when
+        let description = describe_packet(input);
+
+        // then
+        assert_eq!(
+            description,
+            Ok("Operator(Maximum) v7 (3 sub-packets)\n  \
+                Literal 1 v2\n  \
+                Literal 2 v4\n  \
+                Literal 3 v1"
+                .to_string())
+        );
+    }
+
+    #[test]
+    fn describe_packet_should_indent_nested_operators() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let description = describe_packet(input);
+
+        // then
+        assert_eq!(
+            description,
+            Ok("Operator(Sum) v6 (84 bits of sub-packets)\n  \
+                Operator(Sum) v0 (22 bits of sub-packets)\n    \
+                Literal 10 v0\n    \
+                Literal 11 v6\n  \
+                Operator(Sum) v4 (2 sub-packets)\n    \
+                Literal 12 v7\n    \
+                Literal 13 v0"
+                .to_string())
+        );
+    }
+
+    #[test]
+    fn evaluate_should_match_separate_calculations() {
+        // given
+        let inputs = [
+            "D2FE28",
+            "38006F45291200",
+            "EE00D40C823060",
+            "8A004A801A8002F478",
+            "620080001611562C8802118E34",
+            "C0015000016115A2E0802F182340",
+            "A0016C880162017C3686B18A3D4780",
+            "C200B40A82",
+            "04005AC33890",
+            "880086C3E88112",
+            "CE00C43D881120",
+            "D8005AC2A8F0",
+            "F600BC2D8F",
+            "9C005AC2F8F0",
+            "9C0141080250320F1802104A08",
+        ];
+
+        for input in inputs {
+            // when
+            let evaluation = evaluate(input);
+
+            // then
+            assert_eq!(
+                evaluation,
+                Ok((
+                    calculate_sum_of_packet_version_numbers(input, false, &mut |_| {}).unwrap(),
+                    calculate_value_of_packet(input, false, &mut |_| {}).unwrap()
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn packet_from_str_should_skip_whitespace() {
+        // given
+        let input = "D2FE28\r\n";
+
+        // when
+        let packet = Packet::from_str(input);
+
+        // then
+        assert_eq!(
+            packet,
+            Ok(Packet {
+                version: 6,
+                type_: PacketType::LiteralValue { value: 2021 }
+            })
+        );
+    }
+
+    #[test]
+    fn packet_from_str_should_reject_unknown_character() {
+        // given
+        let input = "D2 FG28";
+
+        // when
+        let packet = Packet::from_str(input);
+
+        // then
+        assert_eq!(
+            packet,
+            Err(PacketFromStrError::CharToBits(CharToBitsError::Unknown(
+                'G'
+            )))
+        );
+    }
+
+    #[test]
+    fn packet_from_str_with_empty_input_should_fail_with_input_shape() {
+        // given
+        let input = " \r\n";
+
+        // when
+        let packet = Packet::from_str(input);
+
+        // then
+        assert_eq!(
+            packet,
+            Err(PacketFromStrError::InputShape(InputShapeError::Empty))
+        );
+    }
 }
@@ -1,49 +1,86 @@
 use std::num::ParseIntError;
 use std::str::FromStr;
+use std::time::Instant;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_file, clap_arg_output_format,
+    clap_arg_puzzle_part_time_two, clap_arg_repeat, clap_arg_time, part_from_matches, print_answer,
+    print_elapsed_time, read_input_contents, solve_repeatedly, write_rendered, Part,
+    ReadFileContentsError, WriteRenderedError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day17";
+pub const ABOUT: &str = "My solution for Day 17: Trick Shot";
+
+pub const EXAMPLE: &str = "target area: x=20..30, y=-10..-5\r\n";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 17: Trick Shot")
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day17-input"))
+        .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
         .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day17-input"),
+            Arg::with_name("render")
+                .long("render")
+                .help("prints the trajectory of the highest shot to stderr")
+                .conflicts_with("output_file"),
         )
-        .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_file())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day17Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day17Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => {
-            let count_of_distinct_initial_velocities =
-                count_distinct_initial_velocities(&file_contents)?;
-            println!(
-                "There are {} distinct initial velocity values causing the probe to be within the target area after any step.",
-                count_of_distinct_initial_velocities
+    let solving_start = Instant::now();
+    let optional_highest_shot = match part_from_matches(matches) {
+        Part::Two => {
+            let (initial_velocities, optional_highest_shot) = solve_repeatedly(matches, || {
+                distinct_initial_velocities_and_highest_shot(&file_contents)
+            })?;
+            let count_of_distinct_initial_velocities = initial_velocities.len();
+            print_answer(
+                matches,
+                17,
+                2,
+                count_of_distinct_initial_velocities,
+                format!("There are {} distinct initial velocity values causing the probe to be within the target area after any step.", count_of_distinct_initial_velocities),
             );
+            optional_highest_shot
         }
-        _ => {
-            let highest_y_position_possible = find_highest_y_position_possible(&file_contents)?;
-            println!(
-                "The highest y position possible is {}.",
-                highest_y_position_possible
+        Part::One => {
+            let highest_shot = solve_repeatedly(matches, || find_highest_shot_in(&file_contents))?;
+            let highest_y_position_possible = highest_shot.highest_y_position_reached();
+            print_answer(
+                matches,
+                17,
+                1,
+                highest_y_position_possible,
+                format!(
+                    "The highest y position possible is {}.",
+                    highest_y_position_possible
+                ),
             );
+            Some(highest_shot)
         }
     };
+    if matches.is_present("render") || matches.is_present("output_file") {
+        let highest_shot =
+            optional_highest_shot.ok_or(FindHighestYPositionPossibleError::UnableToFind)?;
+        match matches.value_of("output_file") {
+            Some(output_file) => write_rendered(output_file, highest_shot)?,
+            None => eprint!("{}", highest_shot),
+        }
+    }
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -51,29 +88,61 @@ pub fn handle(matches: &ArgMatches) -> Result<(), Day17Error> {
 pub enum Day17Error {
     #[error("Could not read file contents of \"{0:?}\" ({1})")]
     ReadFileContents(Option<String>, #[source] ReadFileContentsError),
-    #[error("Could not count distinct initial velocities ({0})")]
-    CountDistinctInitialVelocities(#[from] CountDistinctInitialVelocitiesError),
+    #[error("Could not find valid initial velocities ({0})")]
+    ValidInitialVelocities(#[from] ValidInitialVelocitiesError),
     #[error("Could not find highest y position possible ({0})")]
     FindHighestYPositionPossible(#[from] FindHighestYPositionPossibleError),
+    #[error("Could not write trajectory of highest shot ({0})")]
+    WriteRendered(#[from] WriteRenderedError),
 }
 
 pub fn count_distinct_initial_velocities(
     input_target_area: &str,
 ) -> Result<usize, CountDistinctInitialVelocitiesError> {
-    let target_area = TargetArea::from_str(input_target_area)?;
-    Ok(get_all_p(&target_area)
-        .into_iter()
-        .fold(Vec::new(), |mut distinct, simulated_shot| {
-            if !distinct.contains(&simulated_shot) {
-                distinct.push(simulated_shot);
+    Ok(
+        distinct_initial_velocities_and_highest_shot(input_target_area)?
+            .0
+            .len(),
+    )
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum CountDistinctInitialVelocitiesError {
+    #[error(transparent)]
+    ValidInitialVelocities(#[from] ValidInitialVelocitiesError),
+}
+
+pub fn valid_initial_velocities(
+    input_target_area: &str,
+) -> Result<Vec<(i128, i128)>, ValidInitialVelocitiesError> {
+    Ok(distinct_initial_velocities_and_highest_shot(input_target_area)?.0)
+}
+
+type InitialVelocity = (i128, i128);
+
+fn distinct_initial_velocities_and_highest_shot(
+    input_target_area: &str,
+) -> Result<(Vec<InitialVelocity>, Option<SimulatedShot>), ValidInitialVelocitiesError> {
+    let simulated_shots = get_all_p(&TargetArea::from_str(input_target_area)?);
+    let initial_velocities = simulated_shots
+        .iter()
+        .map(|simulated_shot| {
+            (
+                simulated_shot.initial_velocity.x,
+                simulated_shot.initial_velocity.y,
+            )
+        })
+        .fold(Vec::new(), |mut distinct, initial_velocity| {
+            if !distinct.contains(&initial_velocity) {
+                distinct.push(initial_velocity);
             }
             distinct
-        })
-        .len())
+        });
+    Ok((initial_velocities, highest_shot_of(simulated_shots)))
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
-pub enum CountDistinctInitialVelocitiesError {
+pub enum ValidInitialVelocitiesError {
     #[error("Could not parse target area from string ({0})")]
     TargetAreaFromStr(#[from] TargetAreaFromStrError),
 }
@@ -81,20 +150,32 @@ pub enum CountDistinctInitialVelocitiesError {
 pub fn find_highest_y_position_possible(
     input_target_area: &str,
 ) -> Result<i128, FindHighestYPositionPossibleError> {
-    let target_area = TargetArea::from_str(input_target_area)?;
-    get_all_p(&target_area)
-        .into_iter()
-        .reduce(|a, b| {
-            if a.highest_y_position_reached() > b.highest_y_position_reached() {
-                a
-            } else {
-                b
-            }
-        })
-        .map(|simulated_shot| simulated_shot.highest_y_position_reached())
+    Ok(find_highest_shot_in(input_target_area)?.highest_y_position_reached())
+}
+
+pub fn render_highest_shot(
+    input_target_area: &str,
+) -> Result<String, FindHighestYPositionPossibleError> {
+    Ok(find_highest_shot_in(input_target_area)?.to_string())
+}
+
+fn find_highest_shot_in(
+    input_target_area: &str,
+) -> Result<SimulatedShot, FindHighestYPositionPossibleError> {
+    highest_shot_of(get_all_p(&TargetArea::from_str(input_target_area)?))
         .ok_or(FindHighestYPositionPossibleError::UnableToFind)
 }
 
+fn highest_shot_of(simulated_shots: Vec<SimulatedShot>) -> Option<SimulatedShot> {
+    simulated_shots.into_iter().reduce(|a, b| {
+        if a.highest_y_position_reached() > b.highest_y_position_reached() {
+            a
+        } else {
+            b
+        }
+    })
+}
+
 #[derive(Debug, Error, Eq, PartialEq)]
 pub enum FindHighestYPositionPossibleError {
     #[error("Could not parse target area from string ({0})")]
@@ -104,11 +185,17 @@ pub enum FindHighestYPositionPossibleError {
 }
 
 fn get_all_p(target_area: &TargetArea) -> Vec<SimulatedShot> {
+    // any faster shot passes the target area within its first step
+    let (min_x, max_x) = (target_area.min_x.min(0), target_area.max_x.max(0));
+    // the probe passes y=0 again with the negated velocity minus one, so a faster upwards shot
+    // jumps over a target below and never reaches one above that isn't hit on the way up
+    let min_y = target_area.min_y.min(0);
+    let max_y = target_area.min_y.abs().max(target_area.max_y.abs());
     get_all(
-        0,
-        target_area.max_x,
-        -target_area.min_y.abs(),
-        target_area.min_y.abs(),
+        min_x,
+        max_x,
+        min_y,
+        max_y,
         &Position { x: 0, y: 0 },
         target_area,
     )
@@ -137,9 +224,10 @@ fn simulate_shot(
     let mut current_position = *start_position;
     let mut current_velocity = *initial_velocity;
     let mut mid_positions = Vec::new();
-    while current_position.x <= target_area.max_x
-        && current_position.y >= target_area.min_y
-        && !target_area.contains(&current_position)
+    while !target_area.contains(&current_position)
+        && (current_position.x <= target_area.max_x || current_velocity.x < 0)
+        && (current_position.x >= target_area.min_x || current_velocity.x > 0)
+        && (current_position.y >= target_area.min_y || current_velocity.y >= 0)
     {
         if current_position != *start_position {
             mid_positions.push(current_position);
@@ -198,11 +286,13 @@ impl FromStr for TargetArea {
         let parts: [&str; 2] = s
             .trim()
             .strip_prefix("target area: x=")
-            .ok_or(TargetAreaFromStrError::MissingPrefix)?
+            .ok_or_else(|| TargetAreaFromStrError::MissingPrefix(s.to_string()))?
             .split(", y=")
             .collect::<Vec<&str>>()
             .try_into()
-            .map_err(|v: Vec<&str>| TargetAreaFromStrError::NotJustXAndYValues(v.len()))?;
+            .map_err(|v: Vec<&str>| {
+                TargetAreaFromStrError::NotJustXAndYValues(s.to_string(), v.len())
+            })?;
         let parts: [[i128; 2]; 2] = parts
             .into_iter()
             .map(|part| {
@@ -223,20 +313,20 @@ impl FromStr for TargetArea {
             .try_into()
             .unwrap();
         Ok(Self {
-            min_x: parts[0][0],
-            max_x: parts[0][1],
-            min_y: parts[1][0],
-            max_y: parts[1][1],
+            min_x: parts[0][0].min(parts[0][1]),
+            max_x: parts[0][0].max(parts[0][1]),
+            min_y: parts[1][0].min(parts[1][1]),
+            max_y: parts[1][0].max(parts[1][1]),
         })
     }
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
 pub enum TargetAreaFromStrError {
-    #[error("Target area string has wrong format, missing \"target area: x=\" prefix")]
-    MissingPrefix,
-    #[error("Target area string has wrong format, not just x and y values (got {0} part(s)) by splitting on \", y=\"")]
-    NotJustXAndYValues(usize),
+    #[error("Target area string \"{0}\" has wrong format, missing \"target area: x=\" prefix")]
+    MissingPrefix(String),
+    #[error("Target area string \"{0}\" has wrong format, not just x and y values (got {1} part(s)) by splitting on \", y=\"")]
+    NotJustXAndYValues(String, usize),
     #[error("Could not parse value \"{0}\" to integer ({1})")]
     ParseInt(String, #[source] ParseIntError),
     #[error("Target area string has wrong format, not just min and max value (got {0} part(s)) by splitting on \"..\"")]
@@ -258,76 +348,38 @@ impl SimulatedShot {
     }
 
     fn highest_y_position_reached(&self) -> i128 {
-        let mid_positions_max = self
-            .mid_positions
-            .iter()
-            .map(|pos| pos.y)
+        self.path()
+            .map(|position| position.y)
             .max()
-            .unwrap_or(self.start_position.y);
-        self.start_position.y.max(match &self.end_position {
-            None => mid_positions_max,
-            Some(end_position) => end_position.y.max(mid_positions_max),
-        })
+            .unwrap_or(self.start_position.y)
+    }
+
+    fn path(&self) -> impl Iterator<Item = Position> + '_ {
+        std::iter::once(self.start_position)
+            .chain(self.mid_positions.iter().copied())
+            .chain(self.end_position)
     }
 }
 
 impl std::fmt::Display for SimulatedShot {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let min_x = self
-            .mid_positions
-            .iter()
-            .map(|p| p.x)
-            .min()
-            .unwrap_or(self.start_position.x)
-            .min(self.start_position.x)
-            .min(self.target_area.min_x)
-            .min(self.target_area.max_x);
-        let min_x = match &self.end_position {
-            None => min_x,
-            Some(end_position) => min_x.min(end_position.x),
-        };
-
-        let max_x = self
-            .mid_positions
-            .iter()
-            .map(|p| p.x)
-            .max()
-            .unwrap_or(self.start_position.x)
-            .max(self.start_position.x)
-            .max(self.target_area.min_x)
-            .max(self.target_area.max_x);
-        let max_x = match &self.end_position {
-            None => max_x,
-            Some(end_position) => max_x.max(end_position.x),
-        };
-
-        let min_y = self
-            .mid_positions
-            .iter()
-            .map(|p| p.y)
-            .min()
-            .unwrap_or(self.start_position.y)
-            .min(self.start_position.y)
-            .min(self.target_area.min_y)
-            .min(self.target_area.max_y);
-        let min_y = match &self.end_position {
-            None => min_y,
-            Some(end_position) => min_y.min(end_position.y),
-        };
-
-        let max_y = self
-            .mid_positions
-            .iter()
-            .map(|p| p.y)
-            .max()
-            .unwrap_or(self.start_position.y)
-            .max(self.start_position.y)
-            .max(self.target_area.min_y)
-            .max(self.target_area.max_y);
-        let max_y = match &self.end_position {
-            None => max_y,
-            Some(end_position) => max_y.max(end_position.y),
-        };
+        let positions = self
+            .path()
+            .chain([
+                Position {
+                    x: self.target_area.min_x,
+                    y: self.target_area.min_y,
+                },
+                Position {
+                    x: self.target_area.max_x,
+                    y: self.target_area.max_y,
+                },
+            ])
+            .collect::<Vec<Position>>();
+        let min_x = positions.iter().map(|p| p.x).min().unwrap_or(0);
+        let max_x = positions.iter().map(|p| p.x).max().unwrap_or(0);
+        let min_y = positions.iter().map(|p| p.y).min().unwrap_or(0);
+        let max_y = positions.iter().map(|p| p.y).max().unwrap_or(0);
 
         for y in (min_y..=max_y).rev() {
             for x in min_x..=max_x {
@@ -335,7 +387,7 @@ impl std::fmt::Display for SimulatedShot {
                 if self.start_position == position {
                     write!(f, "S")?;
                 } else if self.end_position.map(|p| p == position).unwrap_or(false)
-                    || self.mid_positions.iter().any(|p| *p == position)
+                    || self.mid_positions.contains(&position)
                 {
                     write!(f, "#")?;
                 } else if self.target_area.contains(&position) {
@@ -357,7 +409,7 @@ mod tests {
     #[test]
     fn test_find_highest_y_position_possible() {
         // given
-        let input = "target area: x=20..30, y=-10..-5\r\n";
+        let input = EXAMPLE;
 
         // when
         let highest_y_position_possible = find_highest_y_position_possible(input);
@@ -369,7 +421,7 @@ mod tests {
     #[test]
     fn test_count_distinct_initial_velocities() {
         // given
-        let input = "target area: x=20..30, y=-10..-5\r\n";
+        let input = EXAMPLE;
 
         // when
         let count_of_distinct_initial_velocities = count_distinct_initial_velocities(input);
@@ -378,10 +430,89 @@ mod tests {
         assert_eq!(count_of_distinct_initial_velocities, Ok(112));
     }
 
+    #[test]
+    fn render_highest_shot_should_draw_start_target_and_peak() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let rendered = render_highest_shot(input);
+
+        // then
+        assert!(rendered.is_ok());
+        let rendered = rendered.unwrap();
+        assert!(rendered.contains('S'));
+        assert!(rendered.contains('T'));
+        // the rows go from the peak at y=45 down to the target bottom at y=-10
+        assert_eq!(rendered.lines().filter(|line| !line.is_empty()).count(), 56);
+        assert!(rendered.lines().next().unwrap().contains('#'));
+    }
+
+    #[test]
+    fn get_all_p_should_find_every_shot_into_a_target_above_the_start() {
+        // given
+        let target_area = TargetArea::from_str("target area: x=-30..-20, y=5..10").unwrap();
+
+        // when
+        let got = get_all_p(&target_area);
+
+        // then
+        let expected = get_all(-100, 100, -100, 100, &Position { x: 0, y: 0 }, &target_area);
+        assert!(!expected.is_empty());
+        assert_eq!(got.len(), expected.len());
+        assert!(got
+            .iter()
+            .all(|simulated_shot| expected.contains(simulated_shot)));
+        assert_eq!(
+            find_highest_y_position_possible("target area: x=20..30, y=5..10"),
+            Ok(55)
+        );
+    }
+
+    #[test]
+    fn valid_initial_velocities_should_return_112_distinct_pairs() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let initial_velocities = valid_initial_velocities(input);
+
+        // then
+        assert!(initial_velocities.is_ok());
+        let initial_velocities = initial_velocities.unwrap();
+        assert_eq!(initial_velocities.len(), 112);
+        assert!(initial_velocities.contains(&(7, 2)));
+    }
+
+    #[test]
+    fn find_highest_y_position_possible_with_malformed_target_area_should_name_input() {
+        // given
+        let input = "target zone: x=20..30, y=-10..-5";
+
+        // when
+        let highest_y_position_possible = find_highest_y_position_possible(input);
+
+        // then
+        assert_eq!(
+            highest_y_position_possible,
+            Err(FindHighestYPositionPossibleError::TargetAreaFromStr(
+                TargetAreaFromStrError::MissingPrefix(input.to_string())
+            ))
+        );
+        assert!(highest_y_position_possible
+            .unwrap_err()
+            .to_string()
+            .contains(input));
+        assert!(count_distinct_initial_velocities("target area: x=20..30")
+            .unwrap_err()
+            .to_string()
+            .contains("\"target area: x=20..30\""));
+    }
+
     #[test]
     fn test_get_all_p() {
         // given
-        let input = "target area: x=20..30, y=-10..-5\r\n";
+        let input = EXAMPLE;
 
         // when
         let got = get_all_p(&TargetArea::from_str(input).unwrap())
@@ -643,4 +774,47 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn target_area_from_str_should_normalize_reversed_ranges() {
+        // given
+        let input = "target area: x=30..20, y=-5..-10";
+
+        // when
+        let target_area = TargetArea::from_str(input);
+
+        // then
+        assert_eq!(target_area, TargetArea::from_str(EXAMPLE));
+        assert_eq!(
+            count_distinct_initial_velocities(input),
+            count_distinct_initial_velocities(EXAMPLE)
+        );
+    }
+
+    #[test]
+    fn simulated_shot_path_should_yield_start_mid_and_end_positions() {
+        // given
+        let start_position = Position { x: 0, y: 0 };
+        let initial_velocity = Velocity { x: 7, y: 2 };
+        let target_area = TargetArea::from_str(EXAMPLE).unwrap();
+        let simulated_shot = simulate_shot(&start_position, &initial_velocity, &target_area);
+
+        // when
+        let path = simulated_shot.path().collect::<Vec<Position>>();
+
+        // then
+        assert_eq!(
+            path,
+            vec![
+                Position { x: 0, y: 0 },
+                Position { x: 7, y: 2 },
+                Position { x: 13, y: 3 },
+                Position { x: 18, y: 3 },
+                Position { x: 22, y: 2 },
+                Position { x: 25, y: 0 },
+                Position { x: 27, y: -3 },
+                Position { x: 28, y: -7 },
+            ]
+        );
+    }
 }
@@ -2,51 +2,132 @@ use std::fmt::Display;
 use std::num::ParseIntError;
 use std::ops::Add;
 use std::str::FromStr;
+use std::time::Instant;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_puzzle_part_time_two,
+    clap_arg_repeat, clap_arg_time, part_from_matches, print_answer, print_elapsed_time,
+    read_input_contents, solve_repeatedly, Part, ReadFileContentsError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day18";
+pub const ABOUT: &str = "My solution for Day 18: Snailfish";
+
+pub const EXAMPLE: &str = "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]\r\n\
+    [[[5,[2,8]],4],[5,[[9,9],0]]]\r\n[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]\r\n\
+    [[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]\r\n\
+    [[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]\r\n\
+    [[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]\r\n\
+    [[[[5,4],[7,7]],8],[[8,3],8]]\r\n[[9,3],[[9,9],[6,[4,9]]]]\r\n\
+    [[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]\r\n\
+    [[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]\r\n";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 18: Snailfish")
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day18-input"))
+        .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
         .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day18-input"),
+            Arg::with_name("trace")
+                .long("trace")
+                .help("prints every explode and split step of part one to stderr"),
         )
-        .arg(clap_arg_puzzle_part_time_two())
+        .arg(
+            Arg::with_name("magnitude")
+                .long("magnitude")
+                .value_name("NUMBER")
+                .help("prints the magnitude of a single snailfish number to stderr"),
+        )
+        .arg(Arg::with_name("pairs").long("pairs").help(
+            "prints the ordered pair of lines giving the largest magnitude of part two to stderr",
+        ))
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day18Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day18Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => {
-            let largest_magnitude_of_any_addition =
-                find_largest_magnitude_of_any_addition(&file_contents)?;
-            println!(
-                "The largest magnitude of any addition is {}.",
-                largest_magnitude_of_any_addition
+    let solving_start = Instant::now();
+    if let Some(snailfish_number) = matches.value_of("magnitude") {
+        eprintln!(
+            "The magnitude of {} is {}.",
+            snailfish_number,
+            snailfish_magnitude(snailfish_number)?
+        );
+    }
+    let trace = matches.is_present("trace");
+    match part_from_matches(matches) {
+        Part::Two if trace => return Err(Day18Error::TraceOnlyInPartOne),
+        Part::Two if matches.is_present("pairs") => {
+            let (first_index, second_index, largest_magnitude_of_any_addition) =
+                solve_repeatedly(matches, || largest_magnitude_pair(&file_contents))?;
+            eprintln!(
+                "Adding line {} to line {} gives the largest magnitude.",
+                first_index + 1,
+                second_index + 1
+            );
+            print_answer(
+                matches,
+                18,
+                2,
+                largest_magnitude_of_any_addition,
+                format!(
+                    "The largest magnitude of any addition is {}.",
+                    largest_magnitude_of_any_addition
+                ),
             );
         }
-        _ => {
-            let magnitude_of_added_snailfish_numbers =
-                find_magnitude_of_added_snailfish_numbers(&file_contents)?;
-            println!(
-                "The magnitude of added snailfish numbers is {}.",
-                magnitude_of_added_snailfish_numbers
+        Part::Two => {
+            let largest_magnitude_of_any_addition = solve_repeatedly(matches, || {
+                find_largest_magnitude_of_any_addition(&file_contents)
+            })?;
+            print_answer(
+                matches,
+                18,
+                2,
+                largest_magnitude_of_any_addition,
+                format!(
+                    "The largest magnitude of any addition is {}.",
+                    largest_magnitude_of_any_addition
+                ),
+            );
+        }
+        Part::One => {
+            let mut trace_lines = Vec::new();
+            let magnitude_of_added_snailfish_numbers = solve_repeatedly(matches, || {
+                trace_lines.clear();
+                if trace {
+                    trace_magnitude_of_added_snailfish_numbers(&file_contents, &mut |line| {
+                        trace_lines.push(line)
+                    })
+                } else {
+                    find_magnitude_of_added_snailfish_numbers(&file_contents)
+                }
+            })?;
+            for line in trace_lines {
+                eprintln!("{}", line);
+            }
+            print_answer(
+                matches,
+                18,
+                1,
+                magnitude_of_added_snailfish_numbers,
+                format!(
+                    "The magnitude of added snailfish numbers is {}.",
+                    magnitude_of_added_snailfish_numbers
+                ),
             );
         }
     };
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -56,49 +137,79 @@ pub enum Day18Error {
     ReadFileContents(Option<String>, #[source] ReadFileContentsError),
     #[error("Could not find largest magnitude of any addition ({0})")]
     FindLargestMagnitudeOfAnyAddition(#[from] FindLargestMagnitudeOfAnyAdditionError),
+    #[error("Could not find pair with largest magnitude ({0})")]
+    LargestMagnitudePair(#[from] LargestMagnitudePairError),
     #[error("Could not find magnitude of added snailfish numbers ({0})")]
     FindMagnitudeOfAddedSnailfishNumbers(#[from] FindMagnitudeOfAddedSnailfishNumbersError),
+    #[error("Could not parse snailfish number from string ({0})")]
+    SnailfishNumberFromStr(#[from] SnailfishNumberFromStrError),
+    #[error("The reduction can only be traced for part one")]
+    TraceOnlyInPartOne,
 }
 
 pub fn find_largest_magnitude_of_any_addition(
     snailfish_numbers: &str,
 ) -> Result<u128, FindLargestMagnitudeOfAnyAdditionError> {
+    largest_magnitude_pair(snailfish_numbers)
+        .map(|(_, _, largest_magnitude)| largest_magnitude)
+        .map_err(|error| match error {
+            LargestMagnitudePairError::SnailfishNumberFromStr(error) => {
+                FindLargestMagnitudeOfAnyAdditionError::SnailfishNumberFromStr(error)
+            }
+            LargestMagnitudePairError::MissingSnailfishNumberInInput => {
+                FindLargestMagnitudeOfAnyAdditionError::MissingSnailfishNumberInInput
+            }
+        })
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum FindLargestMagnitudeOfAnyAdditionError {
+    #[error("Could not parse snailfish number from string ({0})")]
+    SnailfishNumberFromStr(#[from] SnailfishNumberFromStrError),
+    #[error("There was no snailfish number in input")]
+    MissingSnailfishNumberInInput,
+}
+
+pub fn largest_magnitude_pair(
+    snailfish_numbers: &str,
+) -> Result<(usize, usize, u128), LargestMagnitudePairError> {
+    // the indices refer to the lines of the input, including the empty ones
     let snailfish_numbers = snailfish_numbers
         .lines()
-        .filter(|line| !line.is_empty())
-        .map(SnailfishNumber::from_str)
-        .collect::<Result<Vec<SnailfishNumber>, SnailfishNumberFromStrError>>()?;
-    (0..snailfish_numbers.len())
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(line_index, line)| Ok((line_index, SnailfishNumber::from_str(line)?)))
+        .collect::<Result<Vec<(usize, SnailfishNumber)>, SnailfishNumberFromStrError>>()?;
+    snailfish_numbers
+        .iter()
         .flat_map(|a| {
-            (0..snailfish_numbers.len())
-                .filter_map(move |b| if a == b { None } else { Some((a, b)) })
+            snailfish_numbers
+                .iter()
+                .filter(move |b| a.0 != b.0)
+                .map(move |b| (a, b))
+        })
+        .map(|((a_index, a), (b_index, b))| {
+            (*a_index, *b_index, (a.clone() + b.clone()).magnitude())
         })
-        .map(|(a, b)| snailfish_numbers[a].clone() + snailfish_numbers[b].clone())
-        .map(|snailfish_number| SnailfishNumber::magnitude(&snailfish_number))
-        .max()
-        .ok_or(FindLargestMagnitudeOfAnyAdditionError::MissingSnailfishNumberInInput)
+        .reduce(|largest, next| if next.2 > largest.2 { next } else { largest })
+        .ok_or(LargestMagnitudePairError::MissingSnailfishNumberInInput)
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
-pub enum FindLargestMagnitudeOfAnyAdditionError {
+pub enum LargestMagnitudePairError {
     #[error("Could not parse snailfish number from string ({0})")]
     SnailfishNumberFromStr(#[from] SnailfishNumberFromStrError),
-    #[error("There was no snailfish number in input")]
+    #[error("There were less than two snailfish numbers in input")]
     MissingSnailfishNumberInInput,
 }
 
 pub fn find_magnitude_of_added_snailfish_numbers(
     snailfish_numbers: &str,
 ) -> Result<u128, FindMagnitudeOfAddedSnailfishNumbersError> {
-    snailfish_numbers
-        .lines()
-        .filter(|line| !line.is_empty())
-        .map(SnailfishNumber::from_str)
-        .collect::<Result<Vec<SnailfishNumber>, SnailfishNumberFromStrError>>()?
-        .into_iter()
-        .reduce(|a, b| a + b)
+    running_sums(snailfish_numbers)?
+        .last()
         .ok_or(FindMagnitudeOfAddedSnailfishNumbersError::MissingSnailfishNumberInInput)
-        .map(|snailfish_number| SnailfishNumber::magnitude(&snailfish_number))
+        .map(SnailfishNumber::magnitude)
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
@@ -109,8 +220,63 @@ pub enum FindMagnitudeOfAddedSnailfishNumbersError {
     MissingSnailfishNumberInInput,
 }
 
+pub fn trace_magnitude_of_added_snailfish_numbers(
+    snailfish_numbers: &str,
+    trace: &mut dyn FnMut(String),
+) -> Result<u128, FindMagnitudeOfAddedSnailfishNumbersError> {
+    parse_snailfish_numbers(snailfish_numbers)?
+        .into_iter()
+        .reduce(|a, b| {
+            trace(format!("  {}\n+ {}", a, b));
+            let (sum, steps) = reduce_with_trace(a, b);
+            for step in steps {
+                trace(format!("  {}", step));
+            }
+            trace(format!("= {}\n", sum));
+            sum
+        })
+        .ok_or(FindMagnitudeOfAddedSnailfishNumbersError::MissingSnailfishNumberInInput)
+        .map(|sum| sum.magnitude())
+}
+
+// The first running sum is the first snailfish number itself, so a single number is its own sum
+pub fn running_sums(
+    snailfish_numbers: &str,
+) -> Result<Vec<SnailfishNumber>, SnailfishNumberFromStrError> {
+    let mut running_sums: Vec<SnailfishNumber> = Vec::new();
+    for snailfish_number in parse_snailfish_numbers(snailfish_numbers)? {
+        let running_sum = match running_sums.last() {
+            Some(last_running_sum) => last_running_sum.clone() + snailfish_number,
+            None => snailfish_number,
+        };
+        running_sums.push(running_sum);
+    }
+    Ok(running_sums)
+}
+
+pub fn snailfish_magnitude(s: &str) -> Result<u128, SnailfishNumberFromStrError> {
+    Ok(SnailfishNumber::from_str(s.trim())?.magnitude())
+}
+
+fn parse_snailfish_numbers(
+    snailfish_numbers: &str,
+) -> Result<Vec<SnailfishNumber>, SnailfishNumberFromStrError> {
+    snailfish_numbers
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(SnailfishNumber::from_str)
+        .collect()
+}
+
+pub fn reduce_with_trace(a: SnailfishNumber, b: SnailfishNumber) -> (SnailfishNumber, Vec<String>) {
+    let mut trace = Vec::new();
+    let mut new = InnerSnailfishNumber::SnailfishNumber(Box::new(a.0), Box::new(b.0));
+    new.reduce(|step| trace.push(step.to_string()));
+    (SnailfishNumber(new), trace)
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
-struct SnailfishNumber(InnerSnailfishNumber);
+pub struct SnailfishNumber(InnerSnailfishNumber);
 
 impl SnailfishNumber {
     fn magnitude(&self) -> u128 {
@@ -155,7 +321,7 @@ pub enum SnailfishNumberFromStrError {
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 enum InnerSnailfishNumber {
-    SimpleNumber(u8),
+    SimpleNumber(u32),
     SnailfishNumber(Box<InnerSnailfishNumber>, Box<InnerSnailfishNumber>),
 }
 
@@ -164,8 +330,8 @@ impl InnerSnailfishNumber {
         fn inner_explode(
             inner: &mut InnerSnailfishNumber,
             depth: u128,
-        ) -> (Option<u8>, Option<InnerSnailfishNumber>, Option<u8>, bool) {
-            fn add_left_most(inner: &mut InnerSnailfishNumber, value: u8) {
+        ) -> (Option<u32>, Option<InnerSnailfishNumber>, Option<u32>, bool) {
+            fn add_left_most(inner: &mut InnerSnailfishNumber, value: u32) {
                 match inner {
                     InnerSnailfishNumber::SimpleNumber(simple_number) => *simple_number += value,
                     InnerSnailfishNumber::SnailfishNumber(left, _) => {
@@ -174,7 +340,7 @@ impl InnerSnailfishNumber {
                 }
             }
 
-            fn add_right_most(inner: &mut InnerSnailfishNumber, value: u8) {
+            fn add_right_most(inner: &mut InnerSnailfishNumber, value: u32) {
                 match inner {
                     InnerSnailfishNumber::SimpleNumber(simple_number) => *simple_number += value,
                     InnerSnailfishNumber::SnailfishNumber(_, right) => {
@@ -194,7 +360,7 @@ impl InnerSnailfishNumber {
                     if let Some(new_left_inner_snailfish_number) =
                         optional_new_left_inner_snailfish_number
                     {
-                        *left = Box::new(new_left_inner_snailfish_number);
+                        **left = new_left_inner_snailfish_number;
                     }
                     if let Some(exploded_left_right) = optional_exploded_left_right {
                         add_left_most(right, exploded_left_right);
@@ -211,7 +377,7 @@ impl InnerSnailfishNumber {
                         if let Some(new_right_inner_snailfish_number) =
                             optional_new_right_inner_snailfish_number
                         {
-                            *right = Box::new(new_right_inner_snailfish_number);
+                            **right = new_right_inner_snailfish_number;
                         }
                         if let Some(exploded_right_left) = optional_exploded_right_left {
                             add_right_most(left, exploded_right_left);
@@ -241,7 +407,7 @@ impl InnerSnailfishNumber {
                             if let Some(new_right_inner_snailfish_number) =
                                 optional_new_right_inner_snailfish_number
                             {
-                                *right = Box::new(new_right_inner_snailfish_number);
+                                **right = new_right_inner_snailfish_number;
                             }
                             if let Some(exploded_right_left) = optional_exploded_right_left {
                                 *left_simple_number += exploded_right_left;
@@ -260,7 +426,7 @@ impl InnerSnailfishNumber {
                         if let Some(new_left_inner_snailfish_number) =
                             optional_new_left_inner_snailfish_number
                         {
-                            *left = Box::new(new_left_inner_snailfish_number);
+                            **left = new_left_inner_snailfish_number;
                         }
                         if let Some(exploded_left_right) = optional_exploded_left_right {
                             *right_simple_number += exploded_left_right;
@@ -290,10 +456,10 @@ impl InnerSnailfishNumber {
                         let half_simple_number = (*simple_number as f64) / 2f64;
                         *inner = InnerSnailfishNumber::SnailfishNumber(
                             Box::new(InnerSnailfishNumber::SimpleNumber(
-                                half_simple_number.floor() as u8,
+                                half_simple_number.floor() as u32,
                             )),
                             Box::new(InnerSnailfishNumber::SimpleNumber(
-                                half_simple_number.ceil() as u8,
+                                half_simple_number.ceil() as u32,
                             )),
                         );
                         true
@@ -323,7 +489,7 @@ impl InnerSnailfishNumber {
         }
     }
 
-    fn biggest_simple_number(&self) -> u8 {
+    fn biggest_simple_number(&self) -> u32 {
         match self {
             InnerSnailfishNumber::SimpleNumber(simple_number) => *simple_number,
             InnerSnailfishNumber::SnailfishNumber(left, right) => left
@@ -332,6 +498,19 @@ impl InnerSnailfishNumber {
         }
     }
 
+    fn reduce<F: FnMut(&InnerSnailfishNumber)>(&mut self, mut after_action: F) {
+        loop {
+            if self.maximum_depth() >= 5 {
+                self.explode();
+            } else if self.biggest_simple_number() >= 10 {
+                self.split();
+            } else {
+                break;
+            }
+            after_action(self);
+        }
+    }
+
     fn magnitude(&self) -> u128 {
         match self {
             InnerSnailfishNumber::SimpleNumber(simple_number) => *simple_number as u128,
@@ -347,15 +526,8 @@ impl Add for InnerSnailfishNumber {
 
     fn add(self, rhs: Self) -> Self::Output {
         let mut new = Self::SnailfishNumber(Box::new(self), Box::new(rhs));
-        loop {
-            if new.maximum_depth() >= 5 {
-                new.explode();
-            } else if new.biggest_simple_number() >= 10 {
-                new.split();
-            } else {
-                break new;
-            }
-        }
+        new.reduce(|_| ());
+        new
     }
 }
 
@@ -372,8 +544,10 @@ impl FromStr for InnerSnailfishNumber {
     type Err = InnerSnailfishNumberFromStrError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // every part is trimmed on its own, so spaces never end up inside a number
+        let s = s.trim_matches(|character: char| character.is_ascii_whitespace());
         if s.starts_with(char::is_numeric) {
-            s.parse::<u8>()
+            s.parse::<u32>()
                 .map(Self::SimpleNumber)
                 .map_err(|error| InnerSnailfishNumberFromStrError::ParseInt(s.to_string(), error))
         } else if s.starts_with('[') {
@@ -381,7 +555,7 @@ impl FromStr for InnerSnailfishNumber {
             let mut optional_middle_index: Option<usize> = None;
             let mut optional_first_part: Option<InnerSnailfishNumber> = None;
             let mut optional_second_part: Option<InnerSnailfishNumber> = None;
-            for (index, character) in s.chars().enumerate() {
+            for (index, character) in s.char_indices() {
                 if character == '[' {
                     opened_brackets += 1;
                 } else if character == ']' {
@@ -432,7 +606,7 @@ impl FromStr for InnerSnailfishNumber {
         } else {
             Err(
                 InnerSnailfishNumberFromStrError::UnexpectedStartingCharacter(
-                    s.chars().collect::<Vec<char>>().get(0).copied(),
+                    s.chars().collect::<Vec<char>>().first().copied(),
                 ),
             )
         }
@@ -466,14 +640,7 @@ mod tests {
     #[test]
     fn test_find_largest_magnitude_of_any_addition() {
         // given
-        let input = "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]\r\n\
-                            [[[5,[2,8]],4],[5,[[9,9],0]]]\r\n[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]\r\n\
-                            [[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]\r\n\
-                            [[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]\r\n\
-                            [[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]\r\n\
-                            [[[[5,4],[7,7]],8],[[8,3],8]]\r\n[[9,3],[[9,9],[6,[4,9]]]]\r\n\
-                            [[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]\r\n\
-                            [[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]\r\n";
+        let input = EXAMPLE;
 
         // when
         let largest_magnitude_of_any_addition = find_largest_magnitude_of_any_addition(input);
@@ -485,14 +652,7 @@ mod tests {
     #[test]
     fn test_find_magnitude_of_added_snailfish_numbers() {
         // given
-        let input = "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]\r\n\
-                            [[[5,[2,8]],4],[5,[[9,9],0]]]\r\n[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]\r\n\
-                            [[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]\r\n\
-                            [[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]\r\n\
-                            [[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]\r\n\
-                            [[[[5,4],[7,7]],8],[[8,3],8]]\r\n[[9,3],[[9,9],[6,[4,9]]]]\r\n\
-                            [[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]\r\n\
-                            [[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]\r\n";
+        let input = EXAMPLE;
 
         // when
         let magnitude_of_added_snailfish_numbers = find_magnitude_of_added_snailfish_numbers(input);
@@ -656,4 +816,155 @@ mod tests {
             InnerSnailfishNumber::from_str("[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]").unwrap()
         );
     }
+
+    #[test]
+    fn inner_snailfish_number_explode_should_not_overflow_u8() {
+        // given
+        let mut input = InnerSnailfishNumber::from_str("[[[[[200,200],100],2],3],4]").unwrap();
+
+        // when
+        input.explode();
+
+        // then
+        assert_eq!(
+            input,
+            InnerSnailfishNumber::from_str("[[[[0,300],2],3],4]").unwrap()
+        );
+        assert_eq!(input.biggest_simple_number(), 300);
+    }
+
+    #[test]
+    fn reduce_with_trace_should_record_every_action() {
+        // given
+        let a = SnailfishNumber::from_str("[[[[4,3],4],4],[7,[[8,4],9]]]").unwrap();
+        let b = SnailfishNumber::from_str("[1,1]").unwrap();
+
+        // when
+        let (sum, trace) = reduce_with_trace(a, b);
+
+        // then
+        assert_eq!(
+            trace,
+            vec![
+                "[[[[0,7],4],[7,[[8,4],9]]],[1,1]]",
+                "[[[[0,7],4],[15,[0,13]]],[1,1]]",
+                "[[[[0,7],4],[[7,8],[0,13]]],[1,1]]",
+                "[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]",
+                "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]",
+            ]
+        );
+        assert_eq!(sum.to_string(), "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]");
+    }
+
+    #[test]
+    fn trace_magnitude_of_added_snailfish_numbers_should_trace_every_addition() {
+        // given
+        let input = "[[[[4,3],4],4],[7,[[8,4],9]]]\r\n[1,1]\r\n";
+        let mut trace = Vec::new();
+
+        // when
+        let magnitude =
+            trace_magnitude_of_added_snailfish_numbers(input, &mut |line| trace.push(line));
+
+        // then
+        assert_eq!(magnitude, Ok(1384));
+        assert_eq!(
+            trace,
+            vec![
+                "  [[[[4,3],4],4],[7,[[8,4],9]]]\n+ [1,1]",
+                "  [[[[0,7],4],[7,[[8,4],9]]],[1,1]]",
+                "  [[[[0,7],4],[15,[0,13]]],[1,1]]",
+                "  [[[[0,7],4],[[7,8],[0,13]]],[1,1]]",
+                "  [[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]",
+                "  [[[[0,7],4],[[7,8],[6,0]]],[8,1]]",
+                "= [[[[0,7],4],[[7,8],[6,0]]],[8,1]]\n",
+            ]
+        );
+    }
+
+    #[test]
+    fn snailfish_magnitude_should_return_documented_magnitudes() {
+        // given
+        let inputs = [
+            ("[[1,2],[[3,4],5]]", 143),
+            ("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", 1384),
+            ("[[[[1,1],[2,2]],[3,3]],[4,4]]", 445),
+            ("[[[[3,0],[5,3]],[4,4]],[5,5]]", 791),
+            ("[[[[5,0],[7,4]],[5,5]],[6,6]]", 1137),
+            (
+                "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]",
+                3488,
+            ),
+        ];
+
+        for (input, expected_magnitude) in inputs {
+            // when
+            let magnitude = snailfish_magnitude(input);
+
+            // then
+            assert_eq!(magnitude, Ok(expected_magnitude));
+        }
+        assert_eq!(
+            snailfish_magnitude("5"),
+            Err(SnailfishNumberFromStrError::ExpectedSnailfishNumberOnTop)
+        );
+    }
+
+    #[test]
+    fn snailfish_number_from_str_should_ignore_whitespace() {
+        // given
+        let input = "[ [1,2] , 3 ]";
+
+        // when
+        let snailfish_number = SnailfishNumber::from_str(input);
+
+        // then
+        assert_eq!(snailfish_number, SnailfishNumber::from_str("[[1,2],3]"));
+        assert!(SnailfishNumber::from_str("[1 2,3]").is_err());
+    }
+
+    #[test]
+    fn running_sums_should_end_with_documented_final_sum() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let running_sums = running_sums(input);
+
+        // then
+        let running_sums = running_sums.unwrap();
+        assert_eq!(running_sums.len(), 10);
+        assert_eq!(
+            running_sums.first().map(SnailfishNumber::to_string),
+            Some("[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]".to_string())
+        );
+        assert_eq!(
+            running_sums.last().map(SnailfishNumber::to_string),
+            Some("[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]".to_string())
+        );
+    }
+
+    #[test]
+    fn largest_magnitude_pair_should_count_empty_lines() {
+        // given
+        let input = format!("\r\n\r\n{}", EXAMPLE);
+
+        // when
+        let largest_magnitude_pair = largest_magnitude_pair(&input);
+
+        // then
+        assert_eq!(largest_magnitude_pair, Ok((10, 2, 3993)));
+    }
+
+    #[test]
+    fn largest_magnitude_pair_should_return_3993() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let largest_magnitude_pair = largest_magnitude_pair(input);
+
+        // then
+        assert_eq!(largest_magnitude_pair, Ok((8, 0, 3993)));
+    }
 }
@@ -1,49 +1,143 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use std::num::ParseIntError;
 use std::ops::{Add, Sub};
 use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::Instant;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_puzzle_part_time_two,
+    clap_arg_repeat, clap_arg_time, part_from_matches, print_answer, print_elapsed_time,
+    read_input_contents, solve_repeatedly, Part, ReadFileContentsError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day19";
+pub const ABOUT: &str = "My solution for Day 19: Beacon Scanner";
+
+pub const EXAMPLE: &str = "--- scanner 0 ---\r\n404,-588,-901\r\n528,-643,409\r\n-838,591,734\r\n\
+    390,-675,-793\r\n-537,-823,-458\r\n-485,-357,347\r\n-345,-311,381\r\n\
+    -661,-816,-575\r\n-876,649,763\r\n-618,-824,-621\r\n553,345,-567\r\n\
+    474,580,667\r\n-447,-329,318\r\n-584,868,-557\r\n544,-627,-890\r\n\
+    564,392,-477\r\n455,729,728\r\n-892,524,684\r\n-689,845,-530\r\n\
+    423,-701,434\r\n7,-33,-71\r\n630,319,-379\r\n443,580,662\r\n\
+    -789,900,-551\r\n459,-707,401\r\n\r\n--- scanner 1 ---\r\n\
+    686,422,578\r\n605,423,415\r\n515,917,-361\r\n-336,658,858\r\n\
+    95,138,22\r\n-476,619,847\r\n-340,-569,-846\r\n567,-361,727\r\n\
+    -460,603,-452\r\n669,-402,600\r\n729,430,532\r\n-500,-761,534\r\n\
+    -322,571,750\r\n-466,-666,-811\r\n-429,-592,574\r\n-355,545,-477\r\n\
+    703,-491,-529\r\n-328,-685,520\r\n413,935,-424\r\n-391,539,-444\r\n\
+    586,-435,557\r\n-364,-763,-893\r\n807,-499,-711\r\n755,-354,-619\r\n\
+    553,889,-390\r\n\r\n--- scanner 2 ---\r\n649,640,665\r\n\
+    682,-795,504\r\n-784,533,-524\r\n-644,584,-595\r\n-588,-843,648\r\n\
+    -30,6,44\r\n-674,560,763\r\n500,723,-460\r\n609,671,-379\r\n\
+    -555,-800,653\r\n-675,-892,-343\r\n697,-426,-610\r\n578,704,681\r\n\
+    493,664,-388\r\n-671,-858,530\r\n-667,343,800\r\n571,-461,-707\r\n\
+    -138,-166,112\r\n-889,563,-600\r\n646,-828,498\r\n640,759,510\r\n\
+    -630,509,768\r\n-681,-892,-333\r\n673,-379,-804\r\n-742,-814,-386\r\n\
+    577,-820,562\r\n\r\n--- scanner 3 ---\r\n-589,542,597\r\n\
+    605,-692,669\r\n-500,565,-823\r\n-660,373,557\r\n-458,-679,-417\r\n\
+    -488,449,543\r\n-626,468,-788\r\n338,-750,-386\r\n528,-832,-391\r\n\
+    562,-778,733\r\n-938,-730,414\r\n543,643,-506\r\n-524,371,-870\r\n\
+    407,773,750\r\n-104,29,83\r\n378,-903,-323\r\n-778,-728,485\r\n\
+    426,699,580\r\n-438,-605,-362\r\n-469,-447,-387\r\n509,732,623\r\n\
+    647,635,-688\r\n-868,-804,481\r\n614,-800,639\r\n595,780,-596\r\n\r\n\
+    --- scanner 4 ---\r\n727,592,562\r\n-293,-554,779\r\n441,611,-461\r\n\
+    -714,465,-776\r\n-743,427,-804\r\n-660,-479,-426\r\n832,-632,460\r\n\
+    927,-485,-438\r\n408,393,-506\r\n466,436,-512\r\n110,16,151\r\n\
+    -258,-428,682\r\n-393,719,612\r\n-211,-452,876\r\n808,-476,-593\r\n\
+    -575,615,604\r\n-485,667,467\r\n-680,325,-822\r\n-627,-443,-432\r\n\
+    872,-547,-609\r\n833,512,582\r\n807,604,487\r\n839,-516,451\r\n\
+    891,-625,532\r\n-652,-548,-490\r\n30,-46,-14\r\n";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 19: Beacon Scanner")
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day19-input"))
+        .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
         .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day19-input"),
+            Arg::with_name("threads")
+                .long("threads")
+                .value_name("THREADS")
+                .help("sets the count of threads matching scanners concurrently")
+                .default_value("1"),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .long("verbose")
+                .help("prints the progress of positioning the scanners to stderr"),
+        )
+        .arg(
+            Arg::with_name("min_overlap")
+                .long("min-overlap")
+                .value_name("BEACONS")
+                .help("sets the count of beacons two scanners need to have in common")
+                .default_value("12"),
         )
-        .arg(clap_arg_puzzle_part_time_two())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day19Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day19Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => {
+    let solving_start = Instant::now();
+    let threads = match matches.value_of("threads") {
+        Some(threads) => threads
+            .parse()
+            .map_err(|error| Day19Error::ParseThreads(threads.to_string(), error))?,
+        None => 1,
+    };
+    let min_overlap = match matches.value_of("min_overlap") {
+        Some(min_overlap) => min_overlap
+            .parse()
+            .map_err(|error| Day19Error::ParseMinOverlap(min_overlap.to_string(), error))?,
+        None => 12,
+    };
+    let verbose = matches.is_present("verbose");
+    match part_from_matches(matches) {
+        Part::Two => {
             let largest_manhattan_distance_between_any_two_scanners =
-                find_largest_manhattan_distance_between_any_two_scanners(&file_contents)?;
-            println!(
-                "The largest Manhattan distance between any two scanners is {}.",
-                largest_manhattan_distance_between_any_two_scanners
+                solve_repeatedly(matches, || {
+                    find_largest_manhattan_distance_between_any_two_scanners(
+                        &file_contents,
+                        threads,
+                        min_overlap,
+                        verbose,
+                    )
+                })?;
+            print_answer(
+                matches,
+                19,
+                2,
+                largest_manhattan_distance_between_any_two_scanners,
+                format!(
+                    "The largest Manhattan distance between any two scanners is {}.",
+                    largest_manhattan_distance_between_any_two_scanners
+                ),
             );
         }
-        _ => {
-            let unique_detected_beacons = count_unique_detected_beacons(&file_contents)?;
-            println!("There are {} beacons.", unique_detected_beacons);
+        Part::One => {
+            let unique_detected_beacons = solve_repeatedly(matches, || {
+                count_unique_detected_beacons(&file_contents, threads, min_overlap, verbose)
+            })?;
+            print_answer(
+                matches,
+                19,
+                1,
+                unique_detected_beacons,
+                format!("There are {} beacons.", unique_detected_beacons),
+            );
         }
     };
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -51,6 +145,10 @@ pub fn handle(matches: &ArgMatches) -> Result<(), Day19Error> {
 pub enum Day19Error {
     #[error("Could not read file contents of \"{0:?}\" ({1})")]
     ReadFileContents(Option<String>, #[source] ReadFileContentsError),
+    #[error("Could not parse threads \"{0}\" ({1})")]
+    ParseThreads(String, #[source] ParseIntError),
+    #[error("Could not parse minimum overlap \"{0}\" ({1})")]
+    ParseMinOverlap(String, #[source] ParseIntError),
     #[error("Could not count unique detected beacons ({0})")]
     CountUniqueDetectedBeacons(#[from] CountUniqueDetectedBeaconsError),
     #[error("Could not find largest Manhattan distance between any two scanners ({0})")]
@@ -61,18 +159,21 @@ pub enum Day19Error {
 
 pub fn count_unique_detected_beacons(
     relative_beacon_positions: &str,
+    threads: usize,
+    min_overlap: usize,
+    verbose: bool,
 ) -> Result<u128, CountUniqueDetectedBeaconsError> {
     let scanner_reports = parse_scanner_reports(relative_beacon_positions)?;
-    let positioned_scanners = position_scanners(scanner_reports)?;
+    let positioned_scanners = position_scanners(
+        scanner_reports,
+        threads,
+        min_overlap,
+        &mut log_progress(verbose),
+    )?;
     let all_absolute_beacon_positions = positioned_scanners
         .into_iter()
         .flat_map(|scanner| scanner.scanned_beacons)
-        .fold(Vec::new(), |mut output, next| {
-            if !output.contains(&next) {
-                output.push(next);
-            }
-            output
-        });
+        .collect::<HashSet<AbsoluteBeaconPosition>>();
     Ok(all_absolute_beacon_positions.len() as u128)
 }
 
@@ -86,9 +187,17 @@ pub enum CountUniqueDetectedBeaconsError {
 
 pub fn find_largest_manhattan_distance_between_any_two_scanners(
     relative_beacon_positions: &str,
+    threads: usize,
+    min_overlap: usize,
+    verbose: bool,
 ) -> Result<u128, FindLargestManhattanDistanceBetweenAnyTwoScannersError> {
     let scanner_reports = parse_scanner_reports(relative_beacon_positions)?;
-    let positioned_scanners = position_scanners(scanner_reports)?;
+    let positioned_scanners = position_scanners(
+        scanner_reports,
+        threads,
+        min_overlap,
+        &mut log_progress(verbose),
+    )?;
     positioned_scanners
         .iter()
         .flat_map(|scanner_a| {
@@ -114,51 +223,111 @@ pub enum FindLargestManhattanDistanceBetweenAnyTwoScannersError {
     MissingScanners,
 }
 
+fn log_progress(verbose: bool) -> impl FnMut(String) {
+    move |message| {
+        if verbose {
+            eprintln!("{}", message);
+        }
+    }
+}
+
 fn position_scanners(
     scanner_reports: Vec<ScannerReport>,
+    threads: usize,
+    min_overlap: usize,
+    log: &mut dyn FnMut(String),
 ) -> Result<Vec<Scanner>, PositionScannersError> {
     let mut scanner_reports = scanner_reports
         .into_iter()
         .map(|scanner_report| (scanner_report.id, scanner_report))
         .collect::<HashMap<ScannerId, ScannerReport>>();
+    let fingerprints = scanner_reports
+        .iter()
+        .map(|(scanner_id, scanner_report)| {
+            (
+                *scanner_id,
+                Fingerprint::of(
+                    &scanner_report
+                        .scanned_beacons
+                        .iter()
+                        .map(|relative_beacon_position| relative_beacon_position.0)
+                        .collect::<Vec<Point3D>>(),
+                ),
+            )
+        })
+        .collect::<HashMap<ScannerId, Fingerprint>>();
 
     let mut positioned_scanners = vec![scanner_reports
         .remove(&ScannerId(0))
         .map(|scanner_report| scanner_report.into_scanner(&Rototranslation3D::identity()))
         .ok_or(PositionScannersError::MissingInitialScanner)?];
 
-    println!(
+    log(format!(
         "Going to position {} scanner reports...",
         scanner_reports.len()
-    );
+    ));
     while !scanner_reports.is_empty() {
-        let mut found = false;
-        let scanner_report_keys = scanner_reports.keys().copied().collect::<Vec<_>>();
-        for scanner_id in scanner_report_keys {
-            if let Some(rototranslation) = positioned_scanners
+        let find_rototranslation = |positioned_scanners: &[Scanner], scanner_id: &ScannerId| {
+            positioned_scanners
                 .iter()
+                .filter(|positioned_scanner| {
+                    fingerprints[&positioned_scanner.id]
+                        .count_common_distances(&fingerprints[scanner_id])
+                        >= min_overlap * min_overlap.saturating_sub(1) / 2
+                })
                 .flat_map(|positioned_scanner| {
-                    find_rototranslation_for_b_with_12_fitting_beacons(
+                    find_rototranslation_for_b_with_fitting_beacons(
                         &positioned_scanner.scanned_beacons,
-                        &scanner_reports[&scanner_id].scanned_beacons,
+                        &scanner_reports[scanner_id].scanned_beacons,
+                        min_overlap,
                     )
                 })
                 .next()
-            {
-                println!("Found {}. rototranslation", positioned_scanners.len());
-                let new_scanner = scanner_reports
-                    .remove(&scanner_id)
-                    .unwrap()
-                    .into_scanner(&rototranslation);
-                positioned_scanners.push(new_scanner);
-                found = true;
-            }
+        };
+        // every round matches against the same positioned scanners in id order,
+        // so the outcome does not depend on the count of threads
+        let mut scanner_report_keys = scanner_reports.keys().copied().collect::<Vec<_>>();
+        scanner_report_keys.sort_unstable_by_key(|scanner_id| scanner_id.0);
+        let chunk_size = scanner_report_keys.len().div_ceil(threads.max(1));
+        let found_rototranslations = std::thread::scope(|scope| {
+            scanner_report_keys
+                .chunks(chunk_size)
+                .map(|scanner_ids| {
+                    let positioned_scanners = &positioned_scanners;
+                    scope.spawn(move || {
+                        scanner_ids
+                            .iter()
+                            .filter_map(|scanner_id| {
+                                find_rototranslation(positioned_scanners, scanner_id)
+                                    .map(|rototranslation| (*scanner_id, rototranslation))
+                            })
+                            .collect::<Vec<(ScannerId, Rototranslation3D)>>()
+                    })
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect::<Vec<(ScannerId, Rototranslation3D)>>()
+        });
+        let found = !found_rototranslations.is_empty();
+        for (scanner_id, rototranslation) in found_rototranslations {
+            log(format!(
+                "Found {}. rototranslation",
+                positioned_scanners.len()
+            ));
+            let new_scanner = scanner_reports
+                .remove(&scanner_id)
+                .unwrap()
+                .into_scanner(&rototranslation);
+            positioned_scanners.push(new_scanner);
         }
         if !found {
-            eprintln!("scanner_reports ({})", scanner_reports.len());
-            eprintln!("{:?}", scanner_reports);
-            eprintln!("{:?}", scanner_reports.keys());
-            panic!("Could not fit any ScannerReport in the already positioned Scanners!");
+            let mut unplaced_scanner_ids = scanner_reports
+                .keys()
+                .map(|scanner_id| scanner_id.0)
+                .collect::<Vec<u128>>();
+            unplaced_scanner_ids.sort_unstable();
+            return Err(PositionScannersError::NoOverlapFound(unplaced_scanner_ids));
         }
     }
     Ok(positioned_scanners)
@@ -168,6 +337,8 @@ fn position_scanners(
 pub enum PositionScannersError {
     #[error("Missing initial scanner (with number 0)")]
     MissingInitialScanner,
+    #[error("Could not find an overlap for scanners {0:?} with the already positioned scanners")]
+    NoOverlapFound(Vec<u128>),
 }
 
 fn parse_scanner_reports(s: &str) -> Result<Vec<ScannerReport>, ParseScannerReportsError> {
@@ -213,6 +384,34 @@ struct Scanner {
     scanned_beacons: Vec<AbsoluteBeaconPosition>,
 }
 
+// squared distances between each pair of beacons, which do not change under rototranslation
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Fingerprint(HashMap<i64, usize>);
+
+impl Fingerprint {
+    fn of(points: &[Point3D]) -> Self {
+        let mut squared_distances = HashMap::new();
+        for (index, point_a) in points.iter().enumerate() {
+            for point_b in &points[(index + 1)..] {
+                let vector = *point_a - *point_b;
+                let squared_distance =
+                    (vector.x as i64).pow(2) + (vector.y as i64).pow(2) + (vector.z as i64).pow(2);
+                *squared_distances.entry(squared_distance).or_insert(0) += 1;
+            }
+        }
+        Self(squared_distances)
+    }
+
+    fn count_common_distances(&self, other: &Fingerprint) -> usize {
+        self.0
+            .iter()
+            .map(|(squared_distance, count)| {
+                (*count).min(other.0.get(squared_distance).copied().unwrap_or(0))
+            })
+            .sum()
+    }
+}
+
 #[derive(Debug, Clone)]
 struct ScannerReport {
     id: ScannerId,
@@ -242,13 +441,13 @@ impl FromStr for ScannerReport {
         let lines = s.lines().collect::<Vec<&str>>();
         Ok(Self {
             id: lines
-                .get(0)
+                .first()
                 .ok_or(ScannerReportFromStrError::MissingHeader)?
                 .strip_prefix("--- scanner ")
                 .ok_or(ScannerReportFromStrError::MissingHeader)?
                 .split_whitespace()
                 .collect::<Vec<&str>>()
-                .get(0)
+                .first()
                 .map(|value| {
                     value.parse::<u128>().map_err(|error| {
                         ScannerReportFromStrError::ParseHeaderNumber(value.to_string(), error)
@@ -299,108 +498,63 @@ impl FromStr for RelativeBeaconPosition {
     type Err = RelativeBeaconPointFromStrError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parsed: [i16; 3] = s
-            .split(',')
-            .map(|line| {
-                line.parse().map_err(|error| {
-                    RelativeBeaconPointFromStrError::ParseInt(line.to_string(), error)
-                })
-            })
-            .collect::<Result<Vec<i16>, RelativeBeaconPointFromStrError>>()?
-            .try_into()
-            .map_err(|v: Vec<i16>| {
-                RelativeBeaconPointFromStrError::UnexpectedCountOfElements(s.to_string(), v.len())
-            })?;
-        Ok(Self(Point3D {
-            x: parsed[0],
-            y: parsed[1],
-            z: parsed[2],
-        }))
+        Ok(Self(Point3D::from_str(s)?))
     }
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
 pub enum RelativeBeaconPointFromStrError {
-    #[error("Could not parse \"{0}\" to integer ({1})")]
-    ParseInt(String, #[source] ParseIntError),
-    #[error("Unexpected count (is {1}, expected 3) of elements encountered in \"{0}\"")]
-    UnexpectedCountOfElements(String, usize),
+    #[error("Could not parse point ({0})")]
+    Point3DFromStr(#[from] Point3DFromStrError),
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
 struct AbsoluteBeaconPosition(Point3D);
 
 impl Display for AbsoluteBeaconPosition {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{},{},{}", self.0.x, self.0.y, self.0.z)
+        write!(f, "{}", self.0)
     }
 }
 
 #[derive(Debug, Clone)]
 struct AbsoluteScannerPosition(Point3D);
 
-fn find_rototranslation_for_b_with_12_fitting_beacons(
+fn find_rototranslation_for_b_with_fitting_beacons(
     absolute_beacon_points_a: &[AbsoluteBeaconPosition],
     relative_beacon_points_b: &[RelativeBeaconPosition],
+    min_overlap: usize,
 ) -> Option<Rototranslation3D> {
     let points_a = absolute_beacon_points_a
         .iter()
         .map(|absolute_beacon_point_a| absolute_beacon_point_a.0)
-        .collect::<Vec<Point3D>>();
+        .collect::<HashSet<Point3D>>();
     let points_b = relative_beacon_points_b
         .iter()
         .map(|relative_beacon_point_b| relative_beacon_point_b.0)
         .collect::<Vec<Point3D>>();
 
-    let rotations: [Rotation3D; 24] = (0..=3)
-        .flat_map(|yaw| (0..=3).flat_map(move |pitch| (0..=3).map(move |roll| (yaw, pitch, roll))))
-        .map(|(yaw, pitch, roll)| Rotation3D::from_euler_angles_90_degree(yaw, pitch, roll))
-        .fold(Vec::new(), |mut output, next| {
-            if !output.contains(&next) {
-                output.push(next);
-            }
-            output
-        })
-        .try_into()
-        .unwrap();
-
-    for rotation in rotations {
+    for rotation in Rotation3D::all() {
         let rotated_points_b = points_b
             .iter()
             .map(|point_b| rotation.transform_point(point_b))
             .collect::<Vec<Point3D>>();
 
-        for point_a in points_a.clone() {
-            for rotated_point_b in rotated_points_b.clone() {
-                let translation = point_a - rotated_point_b;
+        for point_a in &points_a {
+            for rotated_point_b in &rotated_points_b {
+                let translation = *point_a - *rotated_point_b;
 
-                let rototranslated_points_b = rotated_points_b
+                let fitting_beacons_count = rotated_points_b
                     .iter()
-                    .map(|rotated_point_b| *rotated_point_b + translation)
-                    .collect::<Vec<Point3D>>();
-
-                let mut found = 1;
-                let mut cloned_points_a = points_a.clone();
-                for rototranslated_point_b in rototranslated_points_b {
-                    let prev = cloned_points_a.len();
-                    cloned_points_a.retain(|point: &Point3D| *point != rototranslated_point_b);
-                    if cloned_points_a.len() < prev {
-                        if (prev - cloned_points_a.len()) != 1 {
-                            eprintln!(
-                                "Retaining deleted {} elements, but 1 was expected!",
-                                prev - cloned_points_a.len()
-                            );
-                        }
-                        found += 1;
-                        if found >= 12 {
-                            return Some(Rototranslation3D {
-                                rotation,
-                                translation: Translation3D {
-                                    vector: translation,
-                                },
-                            });
-                        }
-                    }
+                    .filter(|rotated_point_b| points_a.contains(&(**rotated_point_b + translation)))
+                    .count();
+                if fitting_beacons_count >= min_overlap {
+                    return Some(Rototranslation3D {
+                        rotation: *rotation,
+                        translation: Translation3D {
+                            vector: translation,
+                        },
+                    });
                 }
             }
         }
@@ -421,7 +575,7 @@ impl Vector3D {
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
 struct Point3D {
     x: i16,
     y: i16,
@@ -434,6 +588,44 @@ impl Point3D {
     }
 }
 
+impl Display for Point3D {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+impl FromStr for Point3D {
+    type Err = Point3DFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parsed: [i16; 3] = s
+            .split(',')
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|error| Point3DFromStrError::ParseInt(value.to_string(), error))
+            })
+            .collect::<Result<Vec<i16>, Point3DFromStrError>>()?
+            .try_into()
+            .map_err(|v: Vec<i16>| {
+                Point3DFromStrError::UnexpectedCountOfElements(s.to_string(), v.len())
+            })?;
+        Ok(Self {
+            x: parsed[0],
+            y: parsed[1],
+            z: parsed[2],
+        })
+    }
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum Point3DFromStrError {
+    #[error("Could not parse \"{0}\" to integer ({1})")]
+    ParseInt(String, #[source] ParseIntError),
+    #[error("Unexpected count (is {1}, expected 3) of elements encountered in \"{0}\"")]
+    UnexpectedCountOfElements(String, usize),
+}
+
 impl Add<Vector3D> for Point3D {
     type Output = Point3D;
 
@@ -490,6 +682,25 @@ impl Rotation3D {
         }
     }
 
+    fn all() -> &'static [Rotation3D; 24] {
+        static ROTATIONS: OnceLock<[Rotation3D; 24]> = OnceLock::new();
+        ROTATIONS.get_or_init(|| {
+            (0..=3)
+                .flat_map(|yaw| {
+                    (0..=3).flat_map(move |pitch| (0..=3).map(move |roll| (yaw, pitch, roll)))
+                })
+                .map(|(yaw, pitch, roll)| Rotation3D::from_euler_angles_90_degree(yaw, pitch, roll))
+                .fold(Vec::new(), |mut output, next| {
+                    if !output.contains(&next) {
+                        output.push(next);
+                    }
+                    output
+                })
+                .try_into()
+                .unwrap()
+        })
+    }
+
     fn inner_sin(ypr: i8) -> i8 {
         match ypr % 4 {
             -3 => 1,
@@ -576,46 +787,37 @@ impl Translation3D {
 mod tests {
     use super::*;
 
+    impl Rotation3D {
+        fn compose(&self, other: &Rotation3D) -> Rotation3D {
+            let mut matrix = [[0; 3]; 3];
+            for (row, matrix_row) in matrix.iter_mut().enumerate() {
+                for (column, value) in matrix_row.iter_mut().enumerate() {
+                    *value = (0..3)
+                        .map(|index| self.matrix[row][index] * other.matrix[index][column])
+                        .sum();
+                }
+            }
+            Self { matrix }
+        }
+
+        fn transpose(&self) -> Rotation3D {
+            let mut matrix = [[0; 3]; 3];
+            for (row, matrix_row) in matrix.iter_mut().enumerate() {
+                for (column, value) in matrix_row.iter_mut().enumerate() {
+                    *value = self.matrix[column][row];
+                }
+            }
+            Self { matrix }
+        }
+    }
+
     #[test]
     fn test_count_unique_detected_beacons() {
         // given
-        let input = "--- scanner 0 ---\r\n404,-588,-901\r\n528,-643,409\r\n-838,591,734\r\n\
-                            390,-675,-793\r\n-537,-823,-458\r\n-485,-357,347\r\n-345,-311,381\r\n\
-                            -661,-816,-575\r\n-876,649,763\r\n-618,-824,-621\r\n553,345,-567\r\n\
-                            474,580,667\r\n-447,-329,318\r\n-584,868,-557\r\n544,-627,-890\r\n\
-                            564,392,-477\r\n455,729,728\r\n-892,524,684\r\n-689,845,-530\r\n\
-                            423,-701,434\r\n7,-33,-71\r\n630,319,-379\r\n443,580,662\r\n\
-                            -789,900,-551\r\n459,-707,401\r\n\r\n--- scanner 1 ---\r\n\
-                            686,422,578\r\n605,423,415\r\n515,917,-361\r\n-336,658,858\r\n\
-                            95,138,22\r\n-476,619,847\r\n-340,-569,-846\r\n567,-361,727\r\n\
-                            -460,603,-452\r\n669,-402,600\r\n729,430,532\r\n-500,-761,534\r\n\
-                            -322,571,750\r\n-466,-666,-811\r\n-429,-592,574\r\n-355,545,-477\r\n\
-                            703,-491,-529\r\n-328,-685,520\r\n413,935,-424\r\n-391,539,-444\r\n\
-                            586,-435,557\r\n-364,-763,-893\r\n807,-499,-711\r\n755,-354,-619\r\n\
-                            553,889,-390\r\n\r\n--- scanner 2 ---\r\n649,640,665\r\n\
-                            682,-795,504\r\n-784,533,-524\r\n-644,584,-595\r\n-588,-843,648\r\n\
-                            -30,6,44\r\n-674,560,763\r\n500,723,-460\r\n609,671,-379\r\n\
-                            -555,-800,653\r\n-675,-892,-343\r\n697,-426,-610\r\n578,704,681\r\n\
-                            493,664,-388\r\n-671,-858,530\r\n-667,343,800\r\n571,-461,-707\r\n\
-                            -138,-166,112\r\n-889,563,-600\r\n646,-828,498\r\n640,759,510\r\n\
-                            -630,509,768\r\n-681,-892,-333\r\n673,-379,-804\r\n-742,-814,-386\r\n\
-                            577,-820,562\r\n\r\n--- scanner 3 ---\r\n-589,542,597\r\n\
-                            605,-692,669\r\n-500,565,-823\r\n-660,373,557\r\n-458,-679,-417\r\n\
-                            -488,449,543\r\n-626,468,-788\r\n338,-750,-386\r\n528,-832,-391\r\n\
-                            562,-778,733\r\n-938,-730,414\r\n543,643,-506\r\n-524,371,-870\r\n\
-                            407,773,750\r\n-104,29,83\r\n378,-903,-323\r\n-778,-728,485\r\n\
-                            426,699,580\r\n-438,-605,-362\r\n-469,-447,-387\r\n509,732,623\r\n\
-                            647,635,-688\r\n-868,-804,481\r\n614,-800,639\r\n595,780,-596\r\n\r\n\
-                            --- scanner 4 ---\r\n727,592,562\r\n-293,-554,779\r\n441,611,-461\r\n\
-                            -714,465,-776\r\n-743,427,-804\r\n-660,-479,-426\r\n832,-632,460\r\n\
-                            927,-485,-438\r\n408,393,-506\r\n466,436,-512\r\n110,16,151\r\n\
-                            -258,-428,682\r\n-393,719,612\r\n-211,-452,876\r\n808,-476,-593\r\n\
-                            -575,615,604\r\n-485,667,467\r\n-680,325,-822\r\n-627,-443,-432\r\n\
-                            872,-547,-609\r\n833,512,582\r\n807,604,487\r\n839,-516,451\r\n\
-                            891,-625,532\r\n-652,-548,-490\r\n30,-46,-14\r\n";
+        let input = EXAMPLE;
 
         // when
-        let unique_detected_beacons = count_unique_detected_beacons(input);
+        let unique_detected_beacons = count_unique_detected_beacons(input, 1, 12, false);
 
         // then
         assert_eq!(unique_detected_beacons, Ok(79));
@@ -623,6 +825,50 @@ mod tests {
 
     #[test]
     fn test_find_largest_manhattan_distance_between_any_two_scanners() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let largest_manhattan_distance_between_any_two_scanners =
+            find_largest_manhattan_distance_between_any_two_scanners(input, 1, 12, false);
+
+        // then
+        assert_eq!(
+            largest_manhattan_distance_between_any_two_scanners,
+            Ok(3621)
+        );
+    }
+
+    #[test]
+    fn count_unique_detected_beacons_should_not_depend_on_threads() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let unique_detected_beacons_with_one_thread =
+            count_unique_detected_beacons(input, 1, 12, false);
+        let unique_detected_beacons_with_four_threads =
+            count_unique_detected_beacons(input, 4, 12, false);
+
+        // then
+        assert_eq!(unique_detected_beacons_with_one_thread, Ok(79));
+        assert_eq!(unique_detected_beacons_with_four_threads, Ok(79));
+        assert_eq!(
+            position_scanners(parse_scanner_reports(input).unwrap(), 1, 12, &mut |_| {})
+                .unwrap()
+                .into_iter()
+                .flat_map(|scanner| scanner.scanned_beacons)
+                .collect::<HashSet<AbsoluteBeaconPosition>>(),
+            position_scanners(parse_scanner_reports(input).unwrap(), 4, 12, &mut |_| {})
+                .unwrap()
+                .into_iter()
+                .flat_map(|scanner| scanner.scanned_beacons)
+                .collect::<HashSet<AbsoluteBeaconPosition>>()
+        );
+    }
+
+    #[test]
+    fn count_unique_detected_beacons_with_disjoint_scanner_groups_should_fail() {
         // given
         let input = "--- scanner 0 ---\r\n404,-588,-901\r\n528,-643,409\r\n-838,591,734\r\n\
                             390,-675,-793\r\n-537,-823,-458\r\n-485,-357,347\r\n-345,-311,381\r\n\
@@ -637,39 +883,94 @@ mod tests {
                             -322,571,750\r\n-466,-666,-811\r\n-429,-592,574\r\n-355,545,-477\r\n\
                             703,-491,-529\r\n-328,-685,520\r\n413,935,-424\r\n-391,539,-444\r\n\
                             586,-435,557\r\n-364,-763,-893\r\n807,-499,-711\r\n755,-354,-619\r\n\
-                            553,889,-390\r\n\r\n--- scanner 2 ---\r\n649,640,665\r\n\
-                            682,-795,504\r\n-784,533,-524\r\n-644,584,-595\r\n-588,-843,648\r\n\
-                            -30,6,44\r\n-674,560,763\r\n500,723,-460\r\n609,671,-379\r\n\
-                            -555,-800,653\r\n-675,-892,-343\r\n697,-426,-610\r\n578,704,681\r\n\
-                            493,664,-388\r\n-671,-858,530\r\n-667,343,800\r\n571,-461,-707\r\n\
-                            -138,-166,112\r\n-889,563,-600\r\n646,-828,498\r\n640,759,510\r\n\
-                            -630,509,768\r\n-681,-892,-333\r\n673,-379,-804\r\n-742,-814,-386\r\n\
-                            577,-820,562\r\n\r\n--- scanner 3 ---\r\n-589,542,597\r\n\
-                            605,-692,669\r\n-500,565,-823\r\n-660,373,557\r\n-458,-679,-417\r\n\
-                            -488,449,543\r\n-626,468,-788\r\n338,-750,-386\r\n528,-832,-391\r\n\
-                            562,-778,733\r\n-938,-730,414\r\n543,643,-506\r\n-524,371,-870\r\n\
-                            407,773,750\r\n-104,29,83\r\n378,-903,-323\r\n-778,-728,485\r\n\
-                            426,699,580\r\n-438,-605,-362\r\n-469,-447,-387\r\n509,732,623\r\n\
-                            647,635,-688\r\n-868,-804,481\r\n614,-800,639\r\n595,780,-596\r\n\r\n\
-                            --- scanner 4 ---\r\n727,592,562\r\n-293,-554,779\r\n441,611,-461\r\n\
-                            -714,465,-776\r\n-743,427,-804\r\n-660,-479,-426\r\n832,-632,460\r\n\
-                            927,-485,-438\r\n408,393,-506\r\n466,436,-512\r\n110,16,151\r\n\
-                            -258,-428,682\r\n-393,719,612\r\n-211,-452,876\r\n808,-476,-593\r\n\
-                            -575,615,604\r\n-485,667,467\r\n-680,325,-822\r\n-627,-443,-432\r\n\
-                            872,-547,-609\r\n833,512,582\r\n807,604,487\r\n839,-516,451\r\n\
-                            891,-625,532\r\n-652,-548,-490\r\n30,-46,-14\r\n";
+                            553,889,-390\r\n\r\n--- scanner 2 ---\r\n1,2,3\r\n4,5,6\r\n\r\n\
+                            --- scanner 3 ---\r\n-1,-2,-3\r\n-4,-5,-6\r\n";
 
         // when
-        let largest_manhattan_distance_between_any_two_scanners =
-            find_largest_manhattan_distance_between_any_two_scanners(input);
+        let unique_detected_beacons = count_unique_detected_beacons(input, 1, 12, false);
 
         // then
         assert_eq!(
-            largest_manhattan_distance_between_any_two_scanners,
-            Ok(3621)
+            unique_detected_beacons,
+            Err(CountUniqueDetectedBeaconsError::PositionScanners(
+                PositionScannersError::NoOverlapFound(vec![2, 3])
+            ))
+        );
+    }
+
+    #[test]
+    fn fingerprint_count_common_distances() {
+        // given
+        let fingerprint_a = Fingerprint::of(&[
+            Point3D { x: 0, y: 0, z: 0 },
+            Point3D { x: 1, y: 0, z: 0 },
+            Point3D { x: 0, y: 2, z: 0 },
+        ]);
+        let fingerprint_b = Fingerprint::of(&[
+            Point3D { x: 5, y: 5, z: 5 },
+            Point3D { x: 5, y: 5, z: 4 },
+            Point3D { x: 7, y: 5, z: 5 },
+            Point3D { x: 9, y: 9, z: 9 },
+        ]);
+
+        // when
+        let common_distances = fingerprint_a.count_common_distances(&fingerprint_b);
+
+        // then
+        assert_eq!(common_distances, 3);
+    }
+
+    #[test]
+    fn point3d_from_str_and_display_should_round_trip() {
+        // given
+        let input = "-1,2,-3";
+
+        // when
+        let point = Point3D::from_str(input);
+
+        // then
+        assert_eq!(point, Ok(Point3D { x: -1, y: 2, z: -3 }));
+        assert_eq!(point.unwrap().to_string(), input);
+        assert!(matches!(
+            Point3D::from_str("1,2"),
+            Err(Point3DFromStrError::UnexpectedCountOfElements(_, 2))
+        ));
+    }
+
+    #[test]
+    fn rotation3d_compose_with_transpose_should_return_identity() {
+        // given
+        let rotation = Rotation3D::from_euler_angles_90_degree(1, 2, 3);
+
+        // when
+        let composed = rotation.compose(&rotation.transpose());
+
+        // then
+        assert_eq!(composed, Rotation3D::identity());
+        assert_eq!(
+            rotation.transpose().compose(&rotation),
+            Rotation3D::identity()
+        );
+        assert_eq!(
+            Rotation3D::from_euler_angles_90_degree(1, 0, 0)
+                .compose(&Rotation3D::from_euler_angles_90_degree(1, 0, 0)),
+            Rotation3D::from_euler_angles_90_degree(2, 0, 0)
         );
     }
 
+    #[test]
+    fn rotation3d_all_should_contain_24_unique_rotations() {
+        // when
+        let rotations = Rotation3D::all();
+
+        // then
+        assert_eq!(rotations.len(), 24);
+        for (index, rotation) in rotations.iter().enumerate() {
+            assert!(!rotations[(index + 1)..].contains(rotation));
+        }
+        assert!(rotations.contains(&Rotation3D::identity()));
+    }
+
     #[test]
     fn rotation3d_inner_sin_cos() {
         assert_eq!(Rotation3D::inner_sin(-3), 1);
@@ -712,4 +1013,51 @@ mod tests {
             Point3D { x: 0, y: 1, z: 0 }
         );
     }
+
+    #[test]
+    fn position_scanners_should_report_progress_only_through_log() {
+        // given
+        let input = EXAMPLE;
+        let mut messages = Vec::new();
+
+        // when
+        let positioned_scanners = position_scanners(
+            parse_scanner_reports(input).unwrap(),
+            1,
+            12,
+            &mut |message| messages.push(message),
+        );
+
+        // then
+        assert_eq!(positioned_scanners.map(|scanners| scanners.len()), Ok(5));
+        assert_eq!(
+            messages,
+            vec![
+                "Going to position 4 scanner reports...",
+                "Found 1. rototranslation",
+                "Found 2. rototranslation",
+                "Found 3. rototranslation",
+                "Found 4. rototranslation",
+            ]
+        );
+    }
+
+    #[test]
+    fn count_unique_detected_beacons_with_lowered_min_overlap_should_match_small_scanners() {
+        // given
+        let input = "--- scanner 0 ---\r\n1,2,3\r\n4,5,6\r\n7,8,10\r\n\r\n\
+                            --- scanner 1 ---\r\n-9,2,3\r\n-6,5,6\r\n-3,8,10\r\n";
+
+        // when
+        let unique_detected_beacons = count_unique_detected_beacons(input, 1, 3, false);
+
+        // then
+        assert_eq!(unique_detected_beacons, Ok(3));
+        assert_eq!(
+            count_unique_detected_beacons(input, 1, 12, false),
+            Err(CountUniqueDetectedBeaconsError::PositionScanners(
+                PositionScannersError::NoOverlapFound(vec![1])
+            ))
+        );
+    }
 }
@@ -1,51 +1,169 @@
 use std::collections::HashMap;
 use std::num::ParseIntError;
 use std::str::FromStr;
+use std::time::Instant;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{clap_arg_puzzle_part_time_two, read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_puzzle_part_time_two,
+    clap_arg_repeat, clap_arg_time, part_from_matches, print_answer, print_elapsed_time,
+    read_input_contents, solve_repeatedly, Part, ReadFileContentsError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day21";
+pub const ABOUT: &str = "My solution for Day 21: Dirac Dice";
+
+pub const EXAMPLE: &str = "Player 1 starting position: 4\r\nPlayer 2 starting position: 8\r\n";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 21: Dirac Dice")
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day21-input"))
+        .arg(clap_arg_puzzle_part_time_two())
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
         .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day21-input"),
+            Arg::with_name("target_score")
+                .short("t")
+                .long("target-score")
+                .value_name("SCORE")
+                .help("sets the score needed to win (defaults to 1000 for part one and 21 for part two)"),
+        )
+        .arg(
+            Arg::with_name("die_min")
+                .long("die-min")
+                .value_name("NUMBER")
+                .help("sets the lowest number of the die")
+                .default_value("1"),
+        )
+        .arg(
+            Arg::with_name("die_max")
+                .long("die-max")
+                .value_name("NUMBER")
+                .help("sets the highest number of the die")
+                .default_value("100"),
+        )
+        .arg(
+            Arg::with_name("die")
+                .long("die")
+                .value_name("DIE")
+                .help("selects the die of part one, either \"deterministic\" or \"seeded:<SEED>\"")
+                .default_value("deterministic"),
+        )
+        .arg(
+            Arg::with_name("dump_players")
+                .long("dump-players")
+                .help("prints the parsed starting position of every player"),
         )
-        .arg(clap_arg_puzzle_part_time_two())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day21Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day21Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    match matches.value_of("puzzle_part").unwrap_or("two") {
-        "two" | "2" => {
-            let winning_universe_count =
-                simulate_quantum_game_and_return_winning_universe_count(&file_contents)?;
-            println!(
-                "The winning player wins in {} universes.",
-                winning_universe_count
+    let solving_start = Instant::now();
+    let optional_target_score = matches
+        .value_of("target_score")
+        .map(|value| {
+            value
+                .parse::<u16>()
+                .map_err(|error| Day21Error::ParseTargetScore(value.to_string(), error))
+        })
+        .transpose()?;
+    if matches.is_present("dump_players") {
+        for (id, position) in starting_positions(&file_contents)? {
+            eprintln!("Player {} starts at position {}.", id, position);
+        }
+    }
+    match part_from_matches(matches) {
+        Part::Two => {
+            let winning_universe_count = solve_repeatedly(matches, || {
+                simulate_quantum_game_and_return_winning_universe_count(
+                    &file_contents,
+                    optional_target_score.unwrap_or(21),
+                )
+            })?
+            .into_values()
+            .max()
+            .unwrap_or(0);
+            print_answer(
+                matches,
+                21,
+                2,
+                winning_universe_count,
+                format!(
+                    "The winning player wins in {} universes.",
+                    winning_universe_count
+                ),
             );
         }
-        _ => {
-            let loosing_score_times_die_rolls =
-                simulate_game_and_return_loosing_score_times_die_rolls(&file_contents)?;
-            println!(
-                "The loosing score multiplied by the die rolls is {}.",
-                loosing_score_times_die_rolls
+        Part::One => {
+            let die_min = matches.value_of("die_min").unwrap_or("1");
+            let die_min = die_min
+                .parse::<u16>()
+                .map_err(|error| Day21Error::ParseDieMin(die_min.to_string(), error))?;
+            let die_max = matches.value_of("die_max").unwrap_or("100");
+            let die_max = die_max
+                .parse::<u16>()
+                .map_err(|error| Day21Error::ParseDieMax(die_max.to_string(), error))?;
+            if die_max < die_min {
+                return Err(Day21Error::InvalidDieRange(die_min, die_max));
+            }
+            let die = matches.value_of("die").unwrap_or("deterministic");
+            if let Some(seed) = die.strip_prefix("seeded:") {
+                let seed = seed
+                    .parse::<u64>()
+                    .map_err(|error| Day21Error::ParseDieSeed(seed.to_string(), error))?;
+                let (loosing_score, die_rolls) = solve_repeatedly(matches, || {
+                    simulate_game_with_seeded_die(
+                        &file_contents,
+                        optional_target_score.unwrap_or(1000),
+                        seed,
+                        die_min,
+                        die_max,
+                    )
+                })?;
+                print_answer(
+                    matches,
+                    21,
+                    1,
+                    loosing_score as u128 * die_rolls,
+                    format!(
+                        "The loosing score is {} after {} die rolls with seed {}.",
+                        loosing_score, die_rolls, seed
+                    ),
+                );
+                print_elapsed_time(matches, solving_start);
+                return Ok(());
+            } else if die != "deterministic" {
+                return Err(Day21Error::UnknownDie(die.to_string()));
+            }
+            let loosing_score_times_die_rolls = solve_repeatedly(matches, || {
+                simulate_game_and_return_loosing_score_times_die_rolls(
+                    &file_contents,
+                    optional_target_score.unwrap_or(1000),
+                    die_min,
+                    die_max,
+                )
+            })?;
+            print_answer(
+                matches,
+                21,
+                1,
+                loosing_score_times_die_rolls,
+                format!(
+                    "The loosing score multiplied by the die rolls is {}.",
+                    loosing_score_times_die_rolls
+                ),
             );
         }
     };
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -53,6 +171,18 @@ pub fn handle(matches: &ArgMatches) -> Result<(), Day21Error> {
 pub enum Day21Error {
     #[error("Could not read file contents of \"{0:?}\" ({1})")]
     ReadFileContents(Option<String>, #[source] ReadFileContentsError),
+    #[error("Could not parse target score \"{0}\" ({1})")]
+    ParseTargetScore(String, #[source] ParseIntError),
+    #[error("Could not parse lowest die number \"{0}\" ({1})")]
+    ParseDieMin(String, #[source] ParseIntError),
+    #[error("Could not parse highest die number \"{0}\" ({1})")]
+    ParseDieMax(String, #[source] ParseIntError),
+    #[error("Highest die number {1} is lower than lowest die number {0}")]
+    InvalidDieRange(u16, u16),
+    #[error("Unknown die \"{0}\"")]
+    UnknownDie(String),
+    #[error("Could not parse die seed \"{0}\" ({1})")]
+    ParseDieSeed(String, #[source] ParseIntError),
     #[error("Could not simulate game and return loosing score multiplied by die rolls ({0})")]
     SimulateGameAndReturnLoosingScoreTimesDieRolls(
         #[from] SimulateGameAndReturnLoosingScoreTimesDieRollsError,
@@ -61,84 +191,60 @@ pub enum Day21Error {
     SimulateQuantumGameAndReturnWinningUniverseCount(
         #[from] SimulateQuantumGameAndReturnWinningUniverseCountError,
     ),
+    #[error("Could not simulate game with seeded die ({0})")]
+    SimulateGameWithSeededDie(#[from] SimulateGameWithSeededDieError),
+    #[error("Could not parse players ({0})")]
+    ParsePlayers(#[from] ParsePlayersError),
 }
 
 pub fn simulate_quantum_game_and_return_winning_universe_count(
     starting_positions: &str,
-) -> Result<u128, SimulateQuantumGameAndReturnWinningUniverseCountError> {
-    enum UniverseSimulationOutput {
-        Universe(Universe),
-        Winner(u8),
-    }
+    target_score: u16,
+) -> Result<HashMap<u8, u128>, SimulateQuantumGameAndReturnWinningUniverseCountError> {
     let players = parse_players(starting_positions)?;
-    let count_of_players = players.len();
-    let mut winning_counters: HashMap<u8, u128> = HashMap::new();
-    let mut remaining_universes = HashMap::new();
-    remaining_universes.insert(Universe(players), 1);
-    let mut current_player = 0;
-    while !remaining_universes.is_empty() {
-        remaining_universes = remaining_universes
-            .into_iter()
-            .map(|(universe, universe_count)| {
-                (1..=27)
-                    .into_iter()
-                    .map(|value| {
-                        (
-                            universe.clone(),
-                            ((value / 9) % 3) + ((value / 3) % 3) + (value % 3) + 3,
-                        )
-                    })
-                    .map(|(mut universe, throw)| {
-                        universe.0[current_player].move_by(throw);
-                        if let Some(winning_player) =
-                            universe.0.iter().find(|player| player.total_score >= 21)
-                        {
-                            UniverseSimulationOutput::Winner(winning_player.id)
-                        } else {
-                            UniverseSimulationOutput::Universe(universe)
-                        }
-                    })
-                    .map(|universe_simulation_output| (universe_simulation_output, universe_count))
-                    .collect::<Vec<(UniverseSimulationOutput, u128)>>()
-            })
-            .flatten()
-            .collect::<Vec<(UniverseSimulationOutput, u128)>>()
-            .into_iter()
-            .filter_map(|(universe_simulation_output, universe_count)| {
-                match universe_simulation_output {
-                    UniverseSimulationOutput::Universe(universe) => {
-                        Some((universe, universe_count))
-                    }
-                    UniverseSimulationOutput::Winner(winner_id) => {
-                        winning_counters
-                            .entry(winner_id)
-                            .and_modify(|v| *v += universe_count)
-                            .or_insert(universe_count);
-                        None
-                    }
-                }
-            })
-            .fold(HashMap::new(), |mut map, next| {
-                map.entry(next.0)
-                    .and_modify(|v| *v += next.1)
-                    .or_insert(next.1);
-                map
-            });
-        current_player = (current_player + 1) % count_of_players;
-        println!(
-            "{} universes remain, {} different ones",
-            remaining_universes
-                .iter()
-                .map(|(_, count)| *count)
-                .sum::<u128>(),
-            remaining_universes.len()
-        );
+    if players.is_empty() {
+        return Err(SimulateQuantumGameAndReturnWinningUniverseCountError::MissingPlayers);
+    }
+    Ok(count_winning_universes(
+        &Universe(players),
+        0,
+        target_score,
+        &mut HashMap::new(),
+    ))
+}
+
+// sum of three rolls of the three-sided Dirac die and in how many universes it occurs
+const DIRAC_ROLL_DISTRIBUTION: [(u32, u128); 7] =
+    [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)];
+
+fn count_winning_universes(
+    universe: &Universe,
+    current_player: usize,
+    target_score: u16,
+    memo: &mut HashMap<(Universe, usize), HashMap<u8, u128>>,
+) -> HashMap<u8, u128> {
+    if let Some(winning_counters) = memo.get(&(universe.clone(), current_player)) {
+        return winning_counters.clone();
     }
+    let mut winning_counters = HashMap::new();
+    for (roll_sum, universe_count) in DIRAC_ROLL_DISTRIBUTION {
+        let mut next_universe = universe.clone();
+        let player = &mut next_universe.0[current_player];
+        player.move_by(roll_sum);
+        if player.total_score >= target_score {
+            *winning_counters.entry(player.id).or_insert(0) += universe_count;
+        } else {
+            let next_player = (current_player + 1) % next_universe.0.len();
+            for (winner_id, winner_universe_count) in
+                count_winning_universes(&next_universe, next_player, target_score, memo)
+            {
+                *winning_counters.entry(winner_id).or_insert(0) +=
+                    winner_universe_count * universe_count;
+            }
+        }
+    }
+    memo.insert((universe.clone(), current_player), winning_counters.clone());
     winning_counters
-        .iter()
-        .map(|entry| *entry.1)
-        .max()
-        .ok_or(SimulateQuantumGameAndReturnWinningUniverseCountError::MissingPlayers)
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
@@ -154,15 +260,14 @@ struct Universe(Vec<Player>);
 
 pub fn simulate_game_and_return_loosing_score_times_die_rolls(
     starting_positions: &str,
+    target_score: u16,
+    die_min: u16,
+    die_max: u16,
 ) -> Result<u128, SimulateGameAndReturnLoosingScoreTimesDieRollsError> {
     let mut players = parse_players(starting_positions)?;
-    let mut deterministic_die = DeterministicDie::with(1, 100);
-    simulate_game(&mut players, &mut deterministic_die, 1000);
-    let loosing_player = players
-        .iter()
-        .reduce(|a, b| if a.total_score < b.total_score { a } else { b })
-        .unwrap();
-    Ok(loosing_player.total_score as u128 * deterministic_die.roll_count)
+    let mut deterministic_die = DeterministicDie::with(die_min, die_max);
+    simulate_game(&mut players, &mut deterministic_die, target_score);
+    Ok(loosing_score(&players) as u128 * deterministic_die.roll_count)
 }
 
 #[derive(Debug, Error, Eq, PartialEq)]
@@ -171,6 +276,40 @@ pub enum SimulateGameAndReturnLoosingScoreTimesDieRollsError {
     ParsePlayers(#[from] ParsePlayersError),
 }
 
+pub fn simulate_game_with_seeded_die(
+    starting_positions: &str,
+    target_score: u16,
+    seed: u64,
+    die_min: u16,
+    die_max: u16,
+) -> Result<(u16, u128), SimulateGameWithSeededDieError> {
+    let mut players = parse_players(starting_positions)?;
+    let mut seeded_die = SeededDie::with(seed, die_min, die_max);
+    simulate_game(&mut players, &mut seeded_die, target_score);
+    Ok((loosing_score(&players), seeded_die.roll_count))
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum SimulateGameWithSeededDieError {
+    #[error("Could not parse players ({0})")]
+    ParsePlayers(#[from] ParsePlayersError),
+}
+
+fn loosing_score(players: &[Player]) -> u16 {
+    players
+        .iter()
+        .map(|player| player.total_score)
+        .min()
+        .unwrap_or(0)
+}
+
+pub fn starting_positions(starting_positions: &str) -> Result<Vec<(u8, u8)>, ParsePlayersError> {
+    Ok(parse_players(starting_positions)?
+        .into_iter()
+        .map(|player| (player.id, player.position))
+        .collect())
+}
+
 fn parse_players(starting_positions: &str) -> Result<Vec<Player>, ParsePlayersError> {
     let mut players = starting_positions
         .lines()
@@ -196,7 +335,8 @@ fn simulate_game<D: Die>(players: &mut [Player], die: &mut D, target_score: u16)
         .iter()
         .any(|player| player.total_score >= target_score)
     {
-        let die_roll = die.roll() + die.roll() + die.roll();
+        // three rolls of the highest die numbers would overflow a u16
+        let die_roll = die.roll() as u32 + die.roll() as u32 + die.roll() as u32;
         players[current_player].move_by(die_roll);
         current_player = (current_player + 1) % players.len();
     }
@@ -225,14 +365,47 @@ impl DeterministicDie {
     }
 }
 
+#[derive(Debug, Copy, Clone)]
+struct SeededDie {
+    state: u64,
+    min_number: u16,
+    max_number: u16,
+    roll_count: u128,
+}
+
+impl SeededDie {
+    fn with(seed: u64, min_inclusive: u16, max_inclusive: u16) -> Self {
+        Self {
+            state: seed,
+            min_number: min_inclusive,
+            max_number: max_inclusive,
+            roll_count: 0,
+        }
+    }
+}
+
+impl Die for SeededDie {
+    fn roll(&mut self) -> u16 {
+        self.roll_count += 1;
+        // linear congruential generator with the constants of Knuth's MMIX
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let range = (self.max_number - self.min_number) as u64 + 1;
+        self.min_number + ((self.state >> 33) % range) as u16
+    }
+}
+
 impl Die for DeterministicDie {
     fn roll(&mut self) -> u16 {
         self.roll_count += 1;
         let output = self.next_number;
-        self.next_number += 1;
-        if self.next_number > self.max_number {
-            self.next_number = self.min_number;
-        }
+        self.next_number = if self.next_number >= self.max_number {
+            self.min_number
+        } else {
+            self.next_number + 1
+        };
         output
     }
 }
@@ -245,12 +418,8 @@ struct Player {
 }
 
 impl Player {
-    fn move_by(&mut self, value: u16) {
-        let mut new_position = self.position as u16 + value;
-        while new_position > 10 {
-            new_position -= 10;
-        }
-        self.position = new_position as u8;
+    fn move_by(&mut self, value: u32) {
+        self.position = ((self.position as u32 - 1 + value) % 10 + 1) as u8;
         self.total_score += self.position as u16;
     }
 }
@@ -323,28 +492,166 @@ pub enum PlayerFromStrError {
 mod tests {
     use super::*;
 
+    struct RecordingDie<D: Die> {
+        inner: D,
+        rolls: Vec<u16>,
+    }
+
+    impl<D: Die> RecordingDie<D> {
+        fn wrap(inner: D) -> Self {
+            Self {
+                inner,
+                rolls: Vec::new(),
+            }
+        }
+
+        fn rolls(&self) -> &[u16] {
+            &self.rolls
+        }
+    }
+
+    impl<D: Die> Die for RecordingDie<D> {
+        fn roll(&mut self) -> u16 {
+            let roll = self.inner.roll();
+            self.rolls.push(roll);
+            roll
+        }
+    }
+
     #[test]
     fn test_simulate_game_and_return_losing_score_times_dice_rolls() {
         // given
-        let input = "Player 1 starting position: 4\r\nPlayer 2 starting position: 8\r\n";
+        let input = EXAMPLE;
 
         // when
         let losing_score_times_dice_rolls =
-            simulate_game_and_return_loosing_score_times_die_rolls(input);
+            simulate_game_and_return_loosing_score_times_die_rolls(input, 1000, 1, 100);
 
         // then
         assert_eq!(losing_score_times_dice_rolls, Ok(739785));
     }
 
+    #[test]
+    fn test_simulate_game_and_return_losing_score_times_dice_rolls_with_target_score_20() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let losing_score_times_dice_rolls =
+            simulate_game_and_return_loosing_score_times_die_rolls(input, 20, 1, 100);
+
+        // then
+        assert_eq!(losing_score_times_dice_rolls, Ok(9 * 15));
+    }
+
+    #[test]
+    fn test_simulate_game_and_return_losing_score_times_dice_rolls_with_die_from_1_to_3() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let losing_score_times_dice_rolls =
+            simulate_game_and_return_loosing_score_times_die_rolls(input, 20, 1, 3);
+
+        // then
+        assert_eq!(losing_score_times_dice_rolls, Ok(18 * 18));
+    }
+
+    #[test]
+    fn simulate_game_with_highest_die_numbers_should_not_overflow() {
+        // given
+        let input = EXAMPLE;
+
+        // when
+        let losing_score_times_dice_rolls_a =
+            simulate_game_and_return_loosing_score_times_die_rolls(input, 1000, 30000, 30000);
+        let losing_score_times_dice_rolls_b =
+            simulate_game_and_return_loosing_score_times_die_rolls(input, 1000, 65535, 65535);
+        let losing_score_times_seeded_dice_rolls =
+            simulate_game_with_seeded_die(input, 1000, 42, 65534, 65535);
+
+        // then
+        assert_eq!(losing_score_times_dice_rolls_a, Ok(375000));
+        assert_eq!(losing_score_times_dice_rolls_b, Ok(772719));
+        assert!(losing_score_times_seeded_dice_rolls.is_ok());
+    }
+
     #[test]
     fn test_simulate_quantum_game_and_return_winning_universe_count() {
         // given
-        let input = "Player 1 starting position: 4\r\nPlayer 2 starting position: 8\r\n";
+        let input = EXAMPLE;
+
+        // when
+        let winning_universe_count =
+            simulate_quantum_game_and_return_winning_universe_count(input, 21);
+
+        // then
+        assert_eq!(
+            winning_universe_count.map(|winning_counters| winning_counters.into_values().max()),
+            Ok(Some(444356092776315))
+        );
+    }
+
+    #[test]
+    fn test_simulate_quantum_game_and_return_winning_universe_count_with_three_players() {
+        // given
+        let input = "Player 1 starting position: 10\r\nPlayer 2 starting position: 10\r\n\
+                            Player 3 starting position: 10\r\n";
+
+        // when
+        let winning_universe_count =
+            simulate_quantum_game_and_return_winning_universe_count(input, 4);
+
+        // then
+        assert_eq!(
+            winning_universe_count,
+            Ok(HashMap::from([(1, 26 + 27), (2, 26), (3, 26)]))
+        );
+    }
+
+    #[test]
+    fn seeded_die_with_same_seed_should_roll_same_sequence() {
+        // given
+        let mut first_die = SeededDie::with(42, 1, 6);
+        let mut second_die = SeededDie::with(42, 1, 6);
+
+        // when
+        let first_rolls = (0..100).map(|_| first_die.roll()).collect::<Vec<u16>>();
+        let second_rolls = (0..100).map(|_| second_die.roll()).collect::<Vec<u16>>();
+
+        // then
+        assert_eq!(first_rolls, second_rolls);
+        assert!(first_rolls.iter().all(|roll| (1..=6).contains(roll)));
+        assert_eq!(first_die.roll_count, 100);
+        assert_eq!(
+            simulate_game_with_seeded_die(EXAMPLE, 1000, 42, 1, 100),
+            simulate_game_with_seeded_die(EXAMPLE, 1000, 42, 1, 100)
+        );
+    }
+
+    #[test]
+    fn deterministic_die_should_roll_one_to_nine_first() {
+        // given
+        let mut players = parse_players(EXAMPLE).unwrap();
+        let mut recording_die = RecordingDie::wrap(DeterministicDie::with(1, 100));
+
+        // when
+        simulate_game(&mut players, &mut recording_die, 1000);
+
+        // then
+        assert_eq!(recording_die.rolls()[..9], [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(recording_die.rolls().len(), 993);
+    }
+
+    #[test]
+    fn starting_positions_should_return_sorted_players() {
+        // given
+        let input = EXAMPLE;
 
         // when
-        let winning_universe_count = simulate_quantum_game_and_return_winning_universe_count(input);
+        let starting_positions = starting_positions(input);
 
         // then
-        assert_eq!(winning_universe_count, Ok(444356092776315));
+        assert_eq!(starting_positions, Ok(vec![(1, 4), (2, 8)]));
     }
 }
@@ -1,36 +1,61 @@
 use std::num::ParseIntError;
 use std::str::FromStr;
+use std::time::Instant;
 
-use clap::{App, Arg, ArgMatches, SubCommand};
+use clap::{App, ArgMatches, SubCommand};
 
 use thiserror::Error;
 
-use super::{read_file_contents, ReadFileContentsError};
+use super::{
+    clap_arg_example, clap_arg_input_file, clap_arg_output_format, clap_arg_repeat, clap_arg_time,
+    print_answer, print_elapsed_time, read_input_contents, solve_repeatedly, ReadFileContentsError,
+};
 
 pub const SUBCOMMAND_NAME: &str = "day22";
+pub const ABOUT: &str = "My solution for Day 22: Reactor Reboot";
+
+pub const EXAMPLE: &str = "on x=-20..26,y=-36..17,z=-47..7\r\non x=-20..33,y=-21..23,z=-26..28\r\n\
+    on x=-22..28,y=-29..23,z=-38..16\r\non x=-46..7,y=-6..46,z=-50..-1\r\n\
+    on x=-49..1,y=-3..46,z=-24..28\r\non x=2..47,y=-22..22,z=-23..27\r\n\
+    on x=-27..23,y=-28..26,z=-21..29\r\non x=-39..5,y=-6..47,z=-3..44\r\n\
+    on x=-30..21,y=-8..43,z=-13..34\r\non x=-22..26,y=-27..20,z=-29..19\r\n\
+    off x=-48..-32,y=26..41,z=-47..-37\r\non x=-12..35,y=6..50,z=-50..-2\r\n\
+    off x=-48..-32,y=-32..-16,z=-15..-5\r\non x=-18..26,y=-33..15,z=-7..46\r\n\
+    off x=-40..-22,y=-38..-28,z=23..41\r\non x=-16..35,y=-41..10,z=-47..6\r\n\
+    off x=-32..-23,y=11..30,z=-14..3\r\non x=-49..-5,y=-3..45,z=-29..18\r\n\
+    off x=18..30,y=-20..-8,z=-3..13\r\non x=-41..9,y=-7..43,z=-33..15\r\n\
+    on x=-54112..-39298,y=-85059..-49293,z=-27449..7877\r\n\
+    on x=967..23432,y=45373..81175,z=27513..53682";
 
 pub fn subcommand() -> App<'static, 'static> {
     SubCommand::with_name(SUBCOMMAND_NAME)
-        .about("My solution for Day 22: Reactor Reboot")
-        .arg(
-            Arg::with_name("input_file")
-                .short("f")
-                .long("file")
-                .value_name("FILE")
-                .help("sets the input file")
-                .default_value("puzzle-inputs/day22-input"),
-        )
+        .about(ABOUT)
+        .arg(clap_arg_input_file("puzzle-inputs/day22-input"))
+        .arg(clap_arg_output_format())
+        .arg(clap_arg_time())
+        .arg(clap_arg_repeat())
+        .arg(clap_arg_example())
 }
 
 pub fn handle(matches: &ArgMatches) -> Result<(), Day22Error> {
     let input_file = matches.value_of("input_file");
-    let file_contents = read_file_contents(input_file)
+    let file_contents = read_input_contents(matches, EXAMPLE)
         .map_err(|error| Day22Error::ReadFileContents(input_file.map(str::to_string), error))?;
-    let count_of_on_cubes_after_reboot_steps = count_on_cubes_after_reboot_steps(&file_contents)?;
-    println!(
-        "The count of on cubes after reboot steps is {}.",
-        count_of_on_cubes_after_reboot_steps
+    let solving_start = Instant::now();
+    let count_of_on_cubes_after_reboot_steps = solve_repeatedly(matches, || {
+        count_on_cubes_after_reboot_steps(&file_contents)
+    })?;
+    print_answer(
+        matches,
+        22,
+        1,
+        count_of_on_cubes_after_reboot_steps,
+        format!(
+            "The count of on cubes after reboot steps is {}.",
+            count_of_on_cubes_after_reboot_steps
+        ),
     );
+    print_elapsed_time(matches, solving_start);
     Ok(())
 }
 
@@ -178,18 +203,13 @@ pub enum RebootStepFromStrError {
     UnexpectedCoordinateParts(usize),
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
 enum CubeStatus {
     On,
+    #[default]
     Off,
 }
 
-impl Default for CubeStatus {
-    fn default() -> Self {
-        Self::Off
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -197,18 +217,7 @@ mod tests {
     #[test]
     fn test_count_on_cubes_after_reboot_steps() {
         // given
-        let input = "on x=-20..26,y=-36..17,z=-47..7\r\non x=-20..33,y=-21..23,z=-26..28\r\n\
-                            on x=-22..28,y=-29..23,z=-38..16\r\non x=-46..7,y=-6..46,z=-50..-1\r\n\
-                            on x=-49..1,y=-3..46,z=-24..28\r\non x=2..47,y=-22..22,z=-23..27\r\n\
-                            on x=-27..23,y=-28..26,z=-21..29\r\non x=-39..5,y=-6..47,z=-3..44\r\n\
-                            on x=-30..21,y=-8..43,z=-13..34\r\non x=-22..26,y=-27..20,z=-29..19\r\n\
-                            off x=-48..-32,y=26..41,z=-47..-37\r\non x=-12..35,y=6..50,z=-50..-2\r\n\
-                            off x=-48..-32,y=-32..-16,z=-15..-5\r\non x=-18..26,y=-33..15,z=-7..46\r\n\
-                            off x=-40..-22,y=-38..-28,z=23..41\r\non x=-16..35,y=-41..10,z=-47..6\r\n\
-                            off x=-32..-23,y=11..30,z=-14..3\r\non x=-49..-5,y=-3..45,z=-29..18\r\n\
-                            off x=18..30,y=-20..-8,z=-3..13\r\non x=-41..9,y=-7..43,z=-33..15\r\n\
-                            on x=-54112..-39298,y=-85059..-49293,z=-27449..7877\r\n\
-                            on x=967..23432,y=45373..81175,z=27513..53682";
+        let input = EXAMPLE;
 
         // when
         let count_of_on_cubes_after_reboot_steps = count_on_cubes_after_reboot_steps(input);
@@ -1,8 +1,10 @@
+use std::fmt::Display;
 use std::fs::File;
 use std::io::Error as IoError;
-use std::io::Read;
+use std::io::{BufRead, BufReader, Read};
+use std::time::{Duration, Instant};
 
-use clap::Arg;
+use clap::{Arg, ArgMatches};
 
 use thiserror::Error;
 
@@ -38,16 +40,138 @@ fn read_file_contents(file_path: Option<&str>) -> Result<String, ReadFileContent
     Ok(content)
 }
 
+pub fn read_file_lines(
+    file_path: Option<&str>,
+) -> Result<impl Iterator<Item = Result<String, IoError>>, ReadFileContentsError> {
+    Ok(BufReader::new(
+        File::open(file_path.ok_or(ReadFileContentsError::MissingFilePath)?)
+            .map_err(ReadFileContentsError::OpeningFile)?,
+    )
+    .lines())
+}
+
 #[derive(Debug, Error)]
 pub enum ReadFileContentsError {
     #[error("Missing file path")]
     MissingFilePath,
-    #[error("Failed opening file ({0})")]
+    #[error("Failed opening file, input files default to \"puzzle-inputs/dayNN-input\" ({0})")]
     OpeningFile(#[source] IoError),
     #[error("Failed reading file ({0})")]
     ReadingFile(#[source] IoError),
 }
 
+pub fn parse_digit_grid(s: &str) -> Result<Vec<Vec<u8>>, GridParseError> {
+    let grid = s
+        .lines()
+        .filter(|line| !line.is_empty())
+        .enumerate()
+        .map(|(line_index, line)| {
+            line.chars()
+                .enumerate()
+                .map(|(column_index, character)| {
+                    character.to_digit(10).map(|digit| digit as u8).ok_or(
+                        GridParseError::InvalidCharacter(
+                            line_index + 1,
+                            column_index + 1,
+                            character,
+                        ),
+                    )
+                })
+                .collect::<Result<Vec<u8>, GridParseError>>()
+        })
+        .collect::<Result<Vec<Vec<u8>>, GridParseError>>()?;
+    if let Some(first_line) = grid.first() {
+        if let Some((line_index, line)) = grid
+            .iter()
+            .enumerate()
+            .find(|(_, line)| line.len() != first_line.len())
+        {
+            return Err(GridParseError::RaggedRow(
+                line_index + 1,
+                line.len(),
+                first_line.len(),
+            ));
+        }
+    }
+    Ok(grid)
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum GridParseError {
+    #[error("Expected a digit in line no. {0} at column {1}, but found '{2}'")]
+    InvalidCharacter(usize, usize, char),
+    #[error("Line no. {0} has {1} digits, but {2} were expected like in the first line")]
+    RaggedRow(usize, usize, usize),
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum InputShapeError {
+    #[error("Input is empty")]
+    Empty,
+    #[error("Input is missing the {0}")]
+    MissingSection(&'static str),
+}
+
+fn neighbors4(
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+) -> impl Iterator<Item = (usize, usize)> {
+    neighbors_with_offsets(x, y, width, height, &[(0, -1), (1, 0), (0, 1), (-1, 0)])
+}
+
+fn neighbors8(
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+) -> impl Iterator<Item = (usize, usize)> {
+    neighbors_with_offsets(
+        x,
+        y,
+        width,
+        height,
+        &[
+            (0, -1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -1),
+        ],
+    )
+}
+
+fn neighbors_with_offsets(
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    offsets: &'static [(isize, isize)],
+) -> impl Iterator<Item = (usize, usize)> {
+    offsets.iter().filter_map(move |(offset_x, offset_y)| {
+        let neighbor_x = x.checked_add_signed(*offset_x)?;
+        let neighbor_y = y.checked_add_signed(*offset_y)?;
+        if neighbor_x < width && neighbor_y < height {
+            Some((neighbor_x, neighbor_y))
+        } else {
+            None
+        }
+    })
+}
+
+fn clap_arg_input_file(default_input_file: &'static str) -> Arg<'static, 'static> {
+    Arg::with_name("input_file")
+        .short("f")
+        .long("file")
+        .value_name("FILE")
+        .help("sets the input file")
+        .default_value(default_input_file)
+}
+
 fn clap_arg_puzzle_part_time_two() -> Arg<'static, 'static> {
     Arg::with_name("puzzle_part")
         .short("p")
@@ -57,3 +181,386 @@ fn clap_arg_puzzle_part_time_two() -> Arg<'static, 'static> {
         .possible_values(&["one", "two", "1", "2"])
         .default_value("two")
 }
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Part {
+    One,
+    Two,
+}
+
+fn part_from_matches(matches: &ArgMatches) -> Part {
+    // clap only lets the possible values through, so a missing value means the default part two
+    match matches.value_of("puzzle_part") {
+        Some("one") | Some("1") => Part::One,
+        _ => Part::Two,
+    }
+}
+
+fn clap_arg_output_format() -> Arg<'static, 'static> {
+    Arg::with_name("output_format")
+        .long("format")
+        .value_name("FORMAT")
+        .help("selects the output format of the answer")
+        .possible_values(&["text", "json"])
+        .default_value("text")
+}
+
+fn clap_arg_time() -> Arg<'static, 'static> {
+    Arg::with_name("time")
+        .long("time")
+        .help("prints the time needed for parsing and solving to stderr")
+}
+
+fn clap_arg_repeat() -> Arg<'static, 'static> {
+    Arg::with_name("repeat")
+        .long("repeat")
+        .value_name("N")
+        .help("runs the solver N times and prints min/median/max durations to stderr")
+        .validator(|value| {
+            value
+                .parse::<usize>()
+                .map(|_| ())
+                .map_err(|error| error.to_string())
+        })
+        .default_value("1")
+}
+
+fn clap_arg_example() -> Arg<'static, 'static> {
+    Arg::with_name("example")
+        .long("example")
+        .help("uses the example input of the puzzle description instead of the input file")
+}
+
+fn clap_arg_output_file() -> Arg<'static, 'static> {
+    Arg::with_name("output_file")
+        .long("output-file")
+        .value_name("FILE")
+        .help("writes the rendered result to FILE (\"-\" for stdout)")
+}
+
+fn write_rendered<D: Display>(output_file: &str, rendered: D) -> Result<(), WriteRenderedError> {
+    if output_file == "-" {
+        print!("{}", rendered);
+        Ok(())
+    } else {
+        std::fs::write(output_file, rendered.to_string())
+            .map_err(|error| WriteRenderedError::WritingFile(output_file.to_string(), error))
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum WriteRenderedError {
+    #[error("Failed writing rendered result to file \"{0}\" ({1})")]
+    WritingFile(String, #[source] IoError),
+}
+
+fn read_input_contents(
+    matches: &ArgMatches,
+    example: &str,
+) -> Result<String, ReadFileContentsError> {
+    if matches.is_present("example") {
+        Ok(example.to_string())
+    } else {
+        read_file_contents(matches.value_of("input_file"))
+    }
+}
+
+fn print_elapsed_time(matches: &ArgMatches, solving_start: Instant) {
+    if matches.is_present("time") {
+        eprintln!("Parsed and solved in {:?}.", solving_start.elapsed());
+    }
+}
+
+fn solve_repeatedly<T, E, F: FnMut() -> Result<T, E>>(
+    matches: &ArgMatches,
+    mut solver: F,
+) -> Result<T, E> {
+    let repeat = matches
+        .value_of("repeat")
+        .and_then(|repeat| repeat.parse::<usize>().ok())
+        .unwrap_or(1)
+        .max(1);
+    let solving_start = Instant::now();
+    let answer = solver()?;
+    let mut durations = vec![solving_start.elapsed()];
+    for _ in 1..repeat {
+        let solving_start = Instant::now();
+        solver()?;
+        durations.push(solving_start.elapsed());
+    }
+    if repeat > 1 {
+        eprintln!("{}", format_durations(&mut durations));
+    }
+    Ok(answer)
+}
+
+fn format_durations(durations: &mut [Duration]) -> String {
+    durations.sort_unstable();
+    format!(
+        "Solved {} times: min {:?}, median {:?}, max {:?}.",
+        durations.len(),
+        durations[0],
+        durations[durations.len() / 2],
+        durations[durations.len() - 1]
+    )
+}
+
+fn print_answer<A: Display>(matches: &ArgMatches, day: u8, part: u8, answer: A, text: String) {
+    match matches.value_of("output_format").unwrap_or("text") {
+        "json" => println!("{}", format_json_answer(day, part, &answer.to_string())),
+        _ => println!("{}", text),
+    }
+}
+
+fn format_json_answer(day: u8, part: u8, answer: &str) -> String {
+    let mut escaped_answer = String::new();
+    for character in answer.chars() {
+        match character {
+            '"' => escaped_answer.push_str("\\\""),
+            '\\' => escaped_answer.push_str("\\\\"),
+            '\n' => escaped_answer.push_str("\\n"),
+            '\r' => escaped_answer.push_str("\\r"),
+            '\t' => escaped_answer.push_str("\\t"),
+            character if character.is_control() => {
+                escaped_answer.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => escaped_answer.push(character),
+        }
+    }
+    format!(
+        "{{\"day\":{},\"part\":{},\"answer\":\"{}\"}}",
+        day, part, escaped_answer
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_from_matches_should_parse_all_spellings() {
+        for (value, expected_part) in [
+            ("1", Part::One),
+            ("one", Part::One),
+            ("2", Part::Two),
+            ("two", Part::Two),
+        ] {
+            // given
+            let matches = day01::subcommand().get_matches_from(vec!["day01", "-p", value]);
+
+            // when
+            let part = part_from_matches(&matches);
+
+            // then
+            assert_eq!(part, expected_part);
+        }
+        assert_eq!(
+            part_from_matches(&day01::subcommand().get_matches_from(vec!["day01"])),
+            Part::Two
+        );
+    }
+
+    #[test]
+    fn parse_digit_grid_should_return_digits() {
+        // given
+        let input = "012\r\n345\r\n";
+
+        // when
+        let grid = parse_digit_grid(input);
+
+        // then
+        assert_eq!(grid, Ok(vec![vec![0, 1, 2], vec![3, 4, 5]]));
+    }
+
+    #[test]
+    fn parse_digit_grid_with_ragged_rows_should_fail() {
+        // given
+        let input = "012\r\n34\r\n567";
+
+        // when
+        let grid = parse_digit_grid(input);
+
+        // then
+        assert_eq!(grid, Err(GridParseError::RaggedRow(2, 2, 3)));
+    }
+
+    #[test]
+    fn parse_digit_grid_with_non_digit_should_fail() {
+        // given
+        let input = "012\r\n3x5";
+
+        // when
+        let grid = parse_digit_grid(input);
+
+        // then
+        assert_eq!(grid, Err(GridParseError::InvalidCharacter(2, 2, 'x')));
+    }
+
+    #[test]
+    fn neighbors4_should_stay_within_grid() {
+        // when
+        let corner = neighbors4(0, 0, 3, 3).collect::<Vec<(usize, usize)>>();
+        let edge = neighbors4(2, 1, 3, 3).collect::<Vec<(usize, usize)>>();
+        let interior = neighbors4(1, 1, 3, 3).collect::<Vec<(usize, usize)>>();
+
+        // then
+        assert_eq!(corner, vec![(1, 0), (0, 1)]);
+        assert_eq!(edge, vec![(2, 0), (2, 2), (1, 1)]);
+        assert_eq!(interior, vec![(1, 0), (2, 1), (1, 2), (0, 1)]);
+    }
+
+    #[test]
+    fn neighbors8_should_stay_within_grid() {
+        // when
+        let corner = neighbors8(2, 2, 3, 3).collect::<Vec<(usize, usize)>>();
+        let edge = neighbors8(1, 0, 3, 3).collect::<Vec<(usize, usize)>>();
+        let interior = neighbors8(1, 1, 3, 3).collect::<Vec<(usize, usize)>>();
+
+        // then
+        assert_eq!(corner, vec![(2, 1), (1, 2), (1, 1)]);
+        assert_eq!(edge, vec![(2, 0), (2, 1), (1, 1), (0, 1), (0, 0)]);
+        assert_eq!(
+            interior,
+            vec![
+                (1, 0),
+                (2, 0),
+                (2, 1),
+                (2, 2),
+                (1, 2),
+                (0, 2),
+                (0, 1),
+                (0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn format_json_answer_of_day16() {
+        // when
+        let json_answer = format_json_answer(16, 2, "1549026292886");
+
+        // then
+        assert_eq!(
+            json_answer,
+            "{\"day\":16,\"part\":2,\"answer\":\"1549026292886\"}"
+        );
+    }
+
+    #[test]
+    fn format_json_answer_should_escape_answer() {
+        // when
+        let json_answer = format_json_answer(13, 2, "#.\"\\\r\n");
+
+        // then
+        assert_eq!(
+            json_answer,
+            "{\"day\":13,\"part\":2,\"answer\":\"#.\\\"\\\\\\r\\n\"}"
+        );
+    }
+
+    #[test]
+    fn day01_handle_should_accept_time_flag() {
+        // given
+        let matches = day01::subcommand().get_matches_from(vec![
+            "day01",
+            "--time",
+            "-f",
+            "puzzle-inputs/day01-input",
+        ]);
+
+        // when
+        let result = day01::handle(&matches);
+
+        // then
+        assert!(matches.is_present("time"));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn day16_subcommand_should_accept_json_format() {
+        // given
+        let matches = day16::subcommand().get_matches_from(vec!["day16", "--format", "json"]);
+
+        // then
+        assert_eq!(matches.value_of("output_format"), Some("json"));
+    }
+
+    #[test]
+    fn read_input_contents_should_use_example_instead_of_file() {
+        // given
+        let matches = day16::subcommand().get_matches_from(vec![
+            "day16",
+            "--example",
+            "-f",
+            "puzzle-inputs/does-not-exist",
+        ]);
+
+        // when
+        let input_contents = read_input_contents(&matches, day16::EXAMPLE);
+
+        // then
+        assert_eq!(input_contents.ok(), Some(day16::EXAMPLE.to_string()));
+        assert!(day16::handle(&matches).is_ok());
+    }
+
+    #[test]
+    fn solve_repeatedly_should_run_solver_n_times_and_return_one_answer() {
+        // given
+        let matches = day01::subcommand().get_matches_from(vec!["day01", "--repeat", "3"]);
+        let mut runs = 0;
+
+        // when
+        let answer = solve_repeatedly(&matches, || -> Result<u128, ()> {
+            runs += 1;
+            Ok(42)
+        });
+
+        // then
+        assert_eq!(answer, Ok(42));
+        assert_eq!(runs, 3);
+        assert!(day01::handle(&matches).is_ok());
+    }
+
+    #[test]
+    fn format_durations_should_report_min_median_max() {
+        // given
+        let mut durations = [3, 1, 2].map(Duration::from_millis);
+
+        // when
+        let formatted = format_durations(&mut durations);
+
+        // then
+        assert_eq!(formatted, "Solved 3 times: min 1ms, median 2ms, max 3ms.");
+    }
+
+    #[test]
+    fn clap_arg_input_file_should_use_default_and_long_name() {
+        // given
+        let app = || clap::App::new("day00").arg(clap_arg_input_file("puzzle-inputs/day00-input"));
+
+        // when
+        let default_matches = app().get_matches_from(vec!["day00"]);
+        let file_matches = app().get_matches_from(vec!["day00", "--file", "other-input"]);
+        let short_matches = app().get_matches_from(vec!["day00", "-f", "short-input"]);
+
+        // then
+        assert_eq!(
+            default_matches.value_of("input_file"),
+            Some("puzzle-inputs/day00-input")
+        );
+        assert_eq!(file_matches.value_of("input_file"), Some("other-input"));
+        assert_eq!(short_matches.value_of("input_file"), Some("short-input"));
+    }
+
+    #[test]
+    fn day04_subcommand_should_default_to_puzzle_inputs_directory() {
+        // given
+        let matches = day04::subcommand().get_matches_from(vec!["day04"]);
+
+        // when
+        let input_file = matches.value_of("input_file");
+
+        // then
+        assert_eq!(input_file, Some("puzzle-inputs/day04-input"));
+    }
+}
@@ -1,42 +1,55 @@
-use clap::{crate_authors, crate_description, crate_version, App, ArgMatches};
+use clap::{crate_authors, crate_description, crate_version, App, Arg, ArgMatches};
 
 use thiserror::Error;
 
-mod lib;
-
-use crate::lib::{
+use aoc2021::{
     day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13,
     day14, day15, day16, day17, day18, day19, day20, day21, day22,
 };
 
+type SubCommandFn = fn() -> App<'static, 'static>;
+
+const SUBCOMMANDS: [(&str, &str, SubCommandFn); 22] = [
+    (day01::SUBCOMMAND_NAME, day01::ABOUT, day01::subcommand),
+    (day02::SUBCOMMAND_NAME, day02::ABOUT, day02::subcommand),
+    (day03::SUBCOMMAND_NAME, day03::ABOUT, day03::subcommand),
+    (day04::SUBCOMMAND_NAME, day04::ABOUT, day04::subcommand),
+    (day05::SUBCOMMAND_NAME, day05::ABOUT, day05::subcommand),
+    (day06::SUBCOMMAND_NAME, day06::ABOUT, day06::subcommand),
+    (day07::SUBCOMMAND_NAME, day07::ABOUT, day07::subcommand),
+    (day08::SUBCOMMAND_NAME, day08::ABOUT, day08::subcommand),
+    (day09::SUBCOMMAND_NAME, day09::ABOUT, day09::subcommand),
+    (day10::SUBCOMMAND_NAME, day10::ABOUT, day10::subcommand),
+    (day11::SUBCOMMAND_NAME, day11::ABOUT, day11::subcommand),
+    (day12::SUBCOMMAND_NAME, day12::ABOUT, day12::subcommand),
+    (day13::SUBCOMMAND_NAME, day13::ABOUT, day13::subcommand),
+    (day14::SUBCOMMAND_NAME, day14::ABOUT, day14::subcommand),
+    (day15::SUBCOMMAND_NAME, day15::ABOUT, day15::subcommand),
+    (day16::SUBCOMMAND_NAME, day16::ABOUT, day16::subcommand),
+    (day17::SUBCOMMAND_NAME, day17::ABOUT, day17::subcommand),
+    (day18::SUBCOMMAND_NAME, day18::ABOUT, day18::subcommand),
+    (day19::SUBCOMMAND_NAME, day19::ABOUT, day19::subcommand),
+    (day20::SUBCOMMAND_NAME, day20::ABOUT, day20::subcommand),
+    (day21::SUBCOMMAND_NAME, day21::ABOUT, day21::subcommand),
+    (day22::SUBCOMMAND_NAME, day22::ABOUT, day22::subcommand),
+];
+
 fn main() {
     let matches = App::new("Advent of Code 2021")
         .version(crate_version!())
         .author(crate_authors!())
         .about(crate_description!())
-        .subcommand(day01::subcommand())
-        .subcommand(day02::subcommand())
-        .subcommand(day03::subcommand())
-        .subcommand(day04::subcommand())
-        .subcommand(day05::subcommand())
-        .subcommand(day06::subcommand())
-        .subcommand(day07::subcommand())
-        .subcommand(day08::subcommand())
-        .subcommand(day09::subcommand())
-        .subcommand(day10::subcommand())
-        .subcommand(day11::subcommand())
-        .subcommand(day12::subcommand())
-        .subcommand(day13::subcommand())
-        .subcommand(day14::subcommand())
-        .subcommand(day15::subcommand())
-        .subcommand(day16::subcommand())
-        .subcommand(day17::subcommand())
-        .subcommand(day18::subcommand())
-        .subcommand(day19::subcommand())
-        .subcommand(day20::subcommand())
-        .subcommand(day21::subcommand())
-        .subcommand(day22::subcommand())
+        .arg(
+            Arg::with_name("list_days")
+                .long("list-days")
+                .help("lists every implemented day and whether it has a part selector"),
+        )
+        .subcommands(SUBCOMMANDS.iter().map(|(_, _, subcommand)| subcommand()))
         .get_matches();
+    if matches.is_present("list_days") {
+        print!("{}", list_days());
+        return;
+    }
     if let Err(error) = handle_matches(matches) {
         eprintln!("Error: {}", error);
     }
@@ -70,16 +83,47 @@ fn handle_matches(matches: ArgMatches) -> Result<(), HandleMatchesError> {
             day22::SUBCOMMAND_NAME => day22::handle(subcommand_matches).map_err(Into::into),
             subcommand_name => Err(HandleMatchesError::SubCommandDoesNotExist(
                 subcommand_name.to_string(),
+                subcommand_names(),
             )),
         },
         None => Err(HandleMatchesError::SubCommandArgumentsAreMissing),
     }
 }
 
+fn list_days() -> String {
+    SUBCOMMANDS
+        .iter()
+        .map(|(subcommand_name, about, subcommand)| {
+            let supports_part_selector = subcommand()
+                .get_matches_from_safe(vec![*subcommand_name, "--part", "1"])
+                .is_ok();
+            format!(
+                "{}: {} ({})\n",
+                subcommand_name,
+                about,
+                if supports_part_selector {
+                    "parts one and two"
+                } else {
+                    "single part"
+                }
+            )
+        })
+        .collect()
+}
+
+fn subcommand_names() -> Vec<&'static str> {
+    let mut subcommand_names = SUBCOMMANDS
+        .iter()
+        .map(|(subcommand_name, _, _)| *subcommand_name)
+        .collect::<Vec<&str>>();
+    subcommand_names.sort_unstable();
+    subcommand_names
+}
+
 #[derive(Debug, Error)]
 enum HandleMatchesError {
-    #[error("SubCommand \"{0}\" does not exist")]
-    SubCommandDoesNotExist(String),
+    #[error("SubCommand \"{0}\" does not exist (available are {})", .1.join(", "))]
+    SubCommandDoesNotExist(String, Vec<&'static str>),
     #[error("Missing arguments for subcommand")]
     SubCommandArgumentsAreMissing,
     #[error(transparent)]
@@ -127,3 +171,37 @@ enum HandleMatchesError {
     #[error(transparent)]
     Day22Error(#[from] day22::Day22Error),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sub_command_does_not_exist_error_should_list_all_days() {
+        // given
+        let error =
+            HandleMatchesError::SubCommandDoesNotExist("day1".to_string(), subcommand_names());
+
+        // when
+        let message = error.to_string();
+
+        // then
+        assert!(
+            message.starts_with("SubCommand \"day1\" does not exist (available are day01, day02, ")
+        );
+        assert!(message.ends_with("day21, day22)"));
+    }
+
+    #[test]
+    fn list_days_should_include_packet_decoder_of_day16() {
+        // when
+        let listing = list_days();
+
+        // then
+        assert_eq!(listing.lines().count(), 22);
+        assert!(
+            listing.contains("day16: My solution for Day 16: Packet Decoder (parts one and two)\n")
+        );
+        assert!(listing.contains("day22: My solution for Day 22: Reactor Reboot (single part)\n"));
+    }
+}